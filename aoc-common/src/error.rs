@@ -0,0 +1,26 @@
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::Part;
+
+#[derive(Debug, Display, Error)]
+pub enum Error {
+    /// I/O error: {0}
+    Io(#[from] std::io::Error),
+    /// Invalid number: {0}
+    ParseInt(#[from] std::num::ParseIntError),
+    /// Invalid input: {0}
+    InvalidInput(String),
+    /// Unexpected argument: {0}
+    InvalidArgument(String),
+    /// Unknown option {0}, expected [PATH | - | --example]
+    UnknownOption(String),
+    /// Invalid part: {0}
+    InvalidPart(u8),
+    /// Day {0} is not solved
+    UnsolvedDay(u8),
+    /// Day {day} part {part} is not solved
+    UnsolvedPart { day: u8, part: Part },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
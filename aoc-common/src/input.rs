@@ -0,0 +1,117 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::{Error, Result, Solution};
+
+/// Resolves a path relative to the calling crate's manifest directory, so inputs are found
+/// regardless of where in the workspace the binary is run from.
+#[macro_export]
+macro_rules! input_path {
+    ($path:literal) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/", $path)
+    };
+}
+
+pub fn get_input_reader<P>(input_file_path: P) -> Result<BufReader<File>>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(input_file_path)?;
+    Ok(BufReader::new(file))
+}
+
+/// Where a day reads its input from, chosen at runtime.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Input {
+    /// The day's puzzle input, `files/input.txt`.
+    #[default]
+    Puzzle,
+    /// The day's example fixture, `files/test.txt`.
+    Example,
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    /// Parses the per-day binary arguments: `[PATH | - | --example]`.
+    pub fn from_args<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        let input = match args.next() {
+            None => Self::Puzzle,
+            Some(arg) if arg.starts_with("--") && arg != "--example" => {
+                return Err(Error::UnknownOption(arg));
+            }
+            Some(arg) => Self::from(arg.as_str()),
+        };
+
+        match args.next() {
+            None => Ok(input),
+            Some(arg) => Err(Error::InvalidArgument(arg)),
+        }
+    }
+
+    /// The file this input reads from, if any.
+    pub fn path<S>(&self) -> Option<PathBuf>
+    where
+        S: Solution,
+    {
+        match self {
+            Self::Puzzle => Some(Path::new(S::FILES_DIR).join("input.txt")),
+            Self::Example => Some(Path::new(S::FILES_DIR).join("test.txt")),
+            Self::Stdin => None,
+            Self::File(path) => Some(path.clone()),
+        }
+    }
+
+    pub fn open<S>(&self) -> Result<Box<dyn BufRead>>
+    where
+        S: Solution,
+    {
+        Ok(match self.path::<S>() {
+            Some(path) => Box::new(get_input_reader(path)?),
+            None => Box::new(std::io::stdin().lock()),
+        })
+    }
+}
+
+impl From<&str> for Input {
+    fn from(value: &str) -> Self {
+        match value {
+            "-" => Self::Stdin,
+            "--example" => Self::Example,
+            path => Self::File(PathBuf::from(path)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_input_from_args() {
+        assert_eq!(Input::from_args(args(&[])).unwrap(), Input::Puzzle);
+        assert_eq!(Input::from_args(args(&["-"])).unwrap(), Input::Stdin);
+        assert_eq!(
+            Input::from_args(args(&["--example"])).unwrap(),
+            Input::Example
+        );
+        assert_eq!(
+            Input::from_args(args(&["big.txt"])).unwrap(),
+            Input::File(PathBuf::from("big.txt"))
+        );
+        assert!(Input::from_args(args(&["a.txt", "b.txt"])).is_err());
+        assert!(matches!(
+            Input::from_args(args(&["--help"])),
+            Err(Error::UnknownOption(option)) if option == "--help"
+        ));
+    }
+}
@@ -0,0 +1,9 @@
+mod error;
+mod input;
+mod report;
+mod solution;
+
+pub use error::{Error, Result};
+pub use input::{get_input_reader, Input};
+pub use report::report;
+pub use solution::{run, solve, solve_parsed, Part, Solution};
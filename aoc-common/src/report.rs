@@ -0,0 +1,10 @@
+use std::fmt::Display;
+
+use crate::Part;
+
+pub fn report<T>(day: u8, part: Part, answer: T)
+where
+    T: Display,
+{
+    println!("Day {day} part {part}: {answer}");
+}
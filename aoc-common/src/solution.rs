@@ -0,0 +1,107 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+use crate::{report, Error, Input, Result};
+
+/// A single day's puzzle. The input is parsed once and both parts are answered from the parsed
+/// form.
+pub trait Solution {
+    const DAY: u8;
+    /// Directory holding the day's `input.txt` and `test.txt`, usually `input_path!("files")`.
+    const FILES_DIR: &'static str;
+
+    type Input;
+    type PartOne: Display;
+    type PartTwo: Display;
+
+    fn parse<R>(reader: R) -> Result<Self::Input>
+    where
+        R: BufRead;
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne>;
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo>;
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(Error::InvalidPart(value)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+pub fn solve<S, R>(reader: R, part: Part) -> Result<String>
+where
+    S: Solution,
+    R: BufRead,
+{
+    let input = S::parse(reader)?;
+    solve_parsed::<S>(&input, part)
+}
+
+pub fn solve_parsed<S>(input: &S::Input, part: Part) -> Result<String>
+where
+    S: Solution,
+{
+    Ok(match part {
+        Part::One => S::part_one(input)?.to_string(),
+        Part::Two => S::part_two(input)?.to_string(),
+    })
+}
+
+/// Entry point for the per-day binaries: parses the input selected on the command line and
+/// reports every solved part. Errors are printed like the `aoc` binary prints them, and exit with
+/// status 1.
+pub fn run<S>()
+where
+    S: Solution,
+{
+    if let Err(error) = run_parts::<S>() {
+        eprintln!("Error: {error}");
+        std::process::exit(1);
+    }
+}
+
+fn run_parts<S>() -> Result<()>
+where
+    S: Solution,
+{
+    let input = Input::from_args(std::env::args().skip(1))?;
+    let input = S::parse(input.open::<S>()?)?;
+    for part in Part::ALL {
+        match solve_parsed::<S>(&input, part) {
+            Ok(answer) => report(S::DAY, part, answer),
+            Err(Error::UnsolvedPart { .. }) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::Path;
+
+use aoc_common::{Error, Solution};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use day_1::Day1;
+use day_2::Day2;
+use day_3::Day3;
+use day_4::Day4;
+
+/// Benchmarks parsing and each part separately against the day's puzzle input, which is read
+/// into memory up front so file I/O is not measured.
+fn bench_day<S>(c: &mut Criterion)
+where
+    S: Solution,
+{
+    let bytes = fs::read(Path::new(S::FILES_DIR).join("input.txt")).expect("input");
+    let input = S::parse(bytes.as_slice()).expect("parse");
+
+    let mut group = c.benchmark_group(format!("day-{}", S::DAY));
+    group.bench_function("parse", |b| {
+        b.iter(|| S::parse(black_box(bytes.as_slice())))
+    });
+    if !matches!(S::part_one(&input), Err(Error::UnsolvedPart { .. })) {
+        group.bench_function("part-1", |b| b.iter(|| S::part_one(black_box(&input))));
+    }
+    if !matches!(S::part_two(&input), Err(Error::UnsolvedPart { .. })) {
+        group.bench_function("part-2", |b| b.iter(|| S::part_two(black_box(&input))));
+    }
+    group.finish();
+}
+
+criterion_group!(
+    days,
+    bench_day::<Day1>,
+    bench_day::<Day2>,
+    bench_day::<Day3>,
+    bench_day::<Day4>
+);
+criterion_main!(days);
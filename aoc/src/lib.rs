@@ -0,0 +1,67 @@
+pub mod timing;
+pub mod verify;
+
+use aoc_common::{Error, Input, Part, Result, Solution};
+
+use day_1::Day1;
+use day_2::Day2;
+use day_3::Day3;
+use day_4::Day4;
+use timing::Timings;
+
+/// A solved day, with its solution erased behind function pointers so days can be listed and
+/// dispatched at runtime.
+pub struct Day {
+    pub day: u8,
+    pub files_dir: &'static str,
+    solve: fn(Part, &Input) -> Result<String>,
+    time: fn(&Input, u32) -> Result<Timings>,
+}
+
+impl Day {
+    const fn new<S>() -> Self
+    where
+        S: Solution,
+    {
+        Self {
+            day: S::DAY,
+            files_dir: S::FILES_DIR,
+            solve: solve_day::<S>,
+            time: timing::time_day::<S>,
+        }
+    }
+
+    pub fn solve(&self, part: Part, input: &Input) -> Result<String> {
+        (self.solve)(part, input)
+    }
+
+    /// Times parsing and both parts, averaged over `iterations` runs.
+    pub fn time(&self, input: &Input, iterations: u32) -> Result<Timings> {
+        (self.time)(input, iterations)
+    }
+}
+
+pub static DAYS: [Day; 4] = [
+    Day::new::<Day1>(),
+    Day::new::<Day2>(),
+    Day::new::<Day3>(),
+    Day::new::<Day4>(),
+];
+
+pub fn find_day(day: u8) -> Result<&'static Day> {
+    DAYS.iter()
+        .find(|entry| entry.day == day)
+        .ok_or(Error::UnsolvedDay(day))
+}
+
+/// Runs one part of a day's solution against the given input.
+pub fn solve(day: u8, part: Part, input: &Input) -> Result<String> {
+    find_day(day)?.solve(part, input)
+}
+
+fn solve_day<S>(part: Part, input: &Input) -> Result<String>
+where
+    S: Solution,
+{
+    aoc_common::solve::<S, _>(input.open::<S>()?, part)
+}
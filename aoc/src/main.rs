@@ -0,0 +1,276 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use aoc::verify::{self, Answers, Status};
+use aoc::{timing, DAYS};
+use aoc_common::{report, Error, Input, Part, Result};
+use clap::{Parser, Subcommand};
+use day_1::{MatchMode, ReportFormat, ReportWriter, Strictness, VocabularyConfig};
+use day_2::color::ColorSet;
+use day_2::game::{DuplicatePolicy, GameLog};
+use day_2::index::{IndexedGames, Order};
+use day_2::query::{Field, Query};
+use day_2::Day2;
+
+#[derive(Debug, Parser)]
+#[command(about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run one part of a day's solution
+    Run {
+        #[arg(long)]
+        day: u8,
+        #[arg(long, value_parser = parse_part)]
+        part: Part,
+        /// Input file, or `-` for stdin; defaults to the day's puzzle input
+        #[arg(long, conflicts_with = "example")]
+        input: Option<String>,
+        /// Use the day's example fixture instead of the puzzle input
+        #[arg(long)]
+        example: bool,
+    },
+    /// Check every solved day against the recorded answers
+    Verify {
+        /// Answers file, defaults to the workspace `answers.toml`
+        #[arg(long)]
+        answers: Option<PathBuf>,
+    },
+    /// List the ids of the day 2 games matching a query, e.g. `red <= 12 and power > 500`
+    Query {
+        query: String,
+        /// Game log, or `-` for stdin; defaults to the day 2 puzzle input
+        #[arg(long, conflicts_with = "example")]
+        input: Option<String>,
+        /// Use the day 2 example fixture instead of the puzzle input
+        #[arg(long)]
+        example: bool,
+        /// How to combine a color listed twice in one draw: sum, max or reject
+        #[arg(long, default_value = "sum")]
+        duplicates: DuplicatePolicy,
+        /// Sort the matches by a field, `id`, `power` or a color, printing its value after each id
+        #[arg(long)]
+        order_by: Option<Field>,
+        /// Sort from the largest value down
+        #[arg(long, requires = "order_by")]
+        descending: bool,
+        /// Print at most this many matches
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Sum the day 1 calibration values of a file on every core, for multi-GB load-test inputs
+    Calibrate {
+        path: PathBuf,
+        /// Which patterns count as digits: digits, words or compound
+        #[arg(long, default_value = "words", value_parser = parse_mode)]
+        mode: MatchMode,
+        /// Vocabulary file to match with instead of `--mode`
+        #[arg(long, conflicts_with = "mode")]
+        vocabulary: Option<PathBuf>,
+        /// Fail on the first line without digits instead of skipping it
+        #[arg(long)]
+        strict: bool,
+        /// Write what was matched on every line to stdout as `jsonl` or `csv`, and the total to
+        /// stderr. Reads the file on one thread
+        #[arg(long)]
+        report: Option<ReportFormat>,
+    },
+    /// Print a table of parse and solve timings for every solved day
+    Time {
+        /// Number of runs each phase is averaged over
+        #[arg(long, default_value_t = 10)]
+        iterations: u32,
+        /// Time against each day's example fixture instead of the puzzle input
+        #[arg(long)]
+        example: bool,
+    },
+}
+
+fn parse_part(value: &str) -> std::result::Result<Part, String> {
+    let number = value.parse::<u8>().map_err(|error| error.to_string())?;
+    Part::try_from(number).map_err(|error| error.to_string())
+}
+
+fn parse_mode(value: &str) -> std::result::Result<MatchMode, String> {
+    match value {
+        "digits" => Ok(MatchMode::Digits),
+        "words" => Ok(MatchMode::Words),
+        "compound" => Ok(MatchMode::Compound),
+        _ => Err(format!("{value:?}, expected digits, words or compound")),
+    }
+}
+
+fn main() {
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("Error: {error}");
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            example,
+        } => {
+            let answer = aoc::solve(day, part, &select_input(input, example))?;
+            report(day, part, answer);
+        }
+        Command::Query {
+            query,
+            input,
+            example,
+            duplicates,
+            order_by,
+            descending,
+            limit,
+        } => {
+            let order = if descending {
+                Order::Descending
+            } else {
+                Order::Ascending
+            };
+            let ranking = order_by.map(|field| (field, order));
+            let limit = limit.unwrap_or(usize::MAX);
+            run_query(
+                &query,
+                &select_input(input, example),
+                duplicates,
+                ranking,
+                limit,
+            )?;
+        }
+        Command::Calibrate {
+            path,
+            mode,
+            vocabulary,
+            strict,
+            report,
+        } => {
+            let mode = match vocabulary {
+                Some(path) => MatchMode::Custom(VocabularyConfig::load(path)?.build()?),
+                None => mode,
+            };
+            let strictness = if strict {
+                Strictness::Strict
+            } else {
+                Strictness::Lenient
+            };
+            match report {
+                Some(format) => run_calibrate_reported(&path, &mode, strictness, format)?,
+                None => run_calibrate(&path, &mode, strictness)?,
+            }
+        }
+        Command::Verify { answers } => {
+            let answers = Answers::load(answers.unwrap_or(verify::ANSWERS_PATH.into()))?;
+            run_verify(&answers)?;
+        }
+        Command::Time {
+            iterations,
+            example,
+        } => {
+            let input = if example {
+                Input::Example
+            } else {
+                Input::Puzzle
+            };
+            run_time(&input, iterations)?;
+        }
+    }
+    Ok(())
+}
+
+fn select_input(input: Option<String>, example: bool) -> Input {
+    match (input, example) {
+        (_, true) => Input::Example,
+        (Some(path), false) => Input::from(path.as_str()),
+        (None, false) => Input::Puzzle,
+    }
+}
+
+fn run_query(
+    query: &str,
+    input: &Input,
+    duplicates: DuplicatePolicy,
+    ranking: Option<(Field, Order)>,
+    limit: usize,
+) -> Result<()> {
+    let query = Query::parse(query)?;
+    let log = GameLog::parse(input.open::<Day2>()?, ColorSet::discovering(), duplicates)?;
+    let indexed_games = log.analyses().collect::<IndexedGames>();
+    let selected = indexed_games.select(&query);
+    match ranking {
+        None => {
+            for id in selected.into_iter().take(limit) {
+                println!("{id}");
+            }
+        }
+        Some((field, order)) => {
+            for (id, analysis) in indexed_games.top_among(selected, &field, order, limit) {
+                println!("{id}\t{}", field.value(id, analysis));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_calibrate(path: &Path, mode: &MatchMode, strictness: Strictness) -> Result<()> {
+    let calibration = day_1::process_file(path, mode, strictness)?;
+    println!("{}", calibration.total);
+    for skipped in &calibration.skipped {
+        eprintln!("skipped: {skipped}");
+    }
+    Ok(())
+}
+
+fn run_calibrate_reported(
+    path: &Path,
+    mode: &MatchMode,
+    strictness: Strictness,
+    format: ReportFormat,
+) -> Result<()> {
+    let document = fs::read_to_string(path)?;
+    let mut report = ReportWriter::new(io::stdout().lock(), format);
+    let calibration =
+        day_1::process_input_reported(document.lines(), mode, strictness, &mut report)?;
+    eprintln!("total: {}", calibration.total);
+    for skipped in &calibration.skipped {
+        eprintln!("skipped: {skipped}");
+    }
+    Ok(())
+}
+
+fn run_verify(answers: &Answers) -> Result<()> {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for outcome in verify::verify(answers) {
+        println!("{outcome}");
+        match outcome.status {
+            Status::Pass => passed += 1,
+            Status::Missing { .. } => missing += 1,
+            Status::Fail { .. } | Status::Error(_) => failed += 1,
+        }
+    }
+    println!("\n{passed} passed, {failed} failed, {missing} missing");
+
+    if failed > 0 {
+        return Err(Error::InvalidInput(format!(
+            "{failed} answers did not verify"
+        )));
+    }
+    Ok(())
+}
+
+fn run_time(input: &Input, iterations: u32) -> Result<()> {
+    println!("{}", timing::header());
+    for day in &DAYS {
+        println!("{}", day.time(input, iterations)?);
+    }
+    Ok(())
+}
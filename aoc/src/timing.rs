@@ -0,0 +1,90 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use aoc_common::{Error, Input, Result, Solution};
+
+/// Mean wall-clock time of each phase of a day's solution. Input is read into memory before
+/// timing, so only parsing and solving are measured.
+#[derive(Debug)]
+pub struct Timings {
+    pub day: u8,
+    pub parse: Duration,
+    /// `None` when the part is not solved.
+    pub part_one: Option<Duration>,
+    pub part_two: Option<Duration>,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_one.unwrap_or_default() + self.part_two.unwrap_or_default()
+    }
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>3} {:>12} {:>12} {:>12} {:>12}",
+            self.day,
+            format_duration(Some(self.parse)),
+            format_duration(self.part_one),
+            format_duration(self.part_two),
+            format_duration(Some(self.total())),
+        )
+    }
+}
+
+pub fn header() -> String {
+    format!(
+        "{:>3} {:>12} {:>12} {:>12} {:>12}",
+        "day", "parse", "part 1", "part 2", "total"
+    )
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{duration:.2?}"),
+        None => "-".to_string(),
+    }
+}
+
+pub(crate) fn time_day<S>(input: &Input, iterations: u32) -> Result<Timings>
+where
+    S: Solution,
+{
+    let mut bytes = Vec::new();
+    input.open::<S>()?.read_to_end(&mut bytes)?;
+
+    let parse = mean(iterations, || S::parse(bytes.as_slice()).map(|_| ()))?;
+    let parsed = S::parse(bytes.as_slice())?;
+    let part_one = optional(mean(iterations, || S::part_one(&parsed).map(|_| ())))?;
+    let part_two = optional(mean(iterations, || S::part_two(&parsed).map(|_| ())))?;
+
+    Ok(Timings {
+        day: S::DAY,
+        parse,
+        part_one,
+        part_two,
+    })
+}
+
+fn mean<F>(iterations: u32, mut f: F) -> Result<Duration>
+where
+    F: FnMut() -> Result<()>,
+{
+    let iterations = iterations.max(1);
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f()?;
+    }
+    Ok(start.elapsed() / iterations)
+}
+
+fn optional(result: Result<Duration>) -> Result<Option<Duration>> {
+    match result {
+        Ok(duration) => Ok(Some(duration)),
+        Err(Error::UnsolvedPart { .. }) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc_common::{input_path, Error, Input, Part, Result};
+use serde::Deserialize;
+
+use crate::{Day, DAYS};
+
+pub static ANSWERS_PATH: &str = input_path!("../answers.toml");
+
+/// Files checked for every day even when no answer is recorded for them.
+static DEFAULT_FILES: [&str; 2] = ["input.txt", "test.txt"];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartAnswers {
+    part_1: Option<String>,
+    part_2: Option<String>,
+}
+
+impl PartAnswers {
+    fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part_1.as_deref(),
+            Part::Two => self.part_2.as_deref(),
+        }
+    }
+}
+
+/// Known-correct answers per day, per input file, per part.
+#[derive(Debug, Default)]
+pub struct Answers {
+    days: BTreeMap<u8, BTreeMap<String, PartAnswers>>,
+}
+
+impl Answers {
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let raw: BTreeMap<String, BTreeMap<String, PartAnswers>> = toml::from_str(contents)
+            .map_err(|error| Error::InvalidInput(format!("answers: {error}")))?;
+
+        let mut days = BTreeMap::new();
+        for (key, files) in raw {
+            let day = key
+                .strip_prefix("day-")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| Error::InvalidInput(format!("answers: invalid day key {key}")))?;
+            days.insert(day, files);
+        }
+
+        Ok(Self { days })
+    }
+
+    pub fn expected(&self, day: u8, file: &str, part: Part) -> Option<&str> {
+        self.days.get(&day)?.get(file)?.get(part)
+    }
+
+    fn files(&self, day: u8) -> impl Iterator<Item = &str> {
+        self.days
+            .get(&day)
+            .into_iter()
+            .flat_map(|files| files.keys().map(String::as_str))
+    }
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing { actual: String },
+    Error(Error),
+}
+
+impl Status {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Pass | Self::Missing { .. })
+    }
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: Part,
+    pub file: String,
+    pub status: Status,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "day {:>2} part {} {:<12}",
+            self.day, self.part, self.file
+        )?;
+        match &self.status {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL    expected {expected}, got {actual}")
+            }
+            Status::Missing { actual } => write!(f, "missing got {actual}"),
+            Status::Error(error) => write!(f, "ERROR   {error}"),
+        }
+    }
+}
+
+/// Runs every solved day against every file with a recorded answer, plus the default puzzle and
+/// example inputs when present.
+pub fn verify(answers: &Answers) -> Vec<Outcome> {
+    DAYS.iter()
+        .flat_map(|day| verify_day(answers, day))
+        .collect()
+}
+
+fn verify_day(answers: &Answers, day: &Day) -> Vec<Outcome> {
+    let mut files = answers
+        .files(day.day)
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    for file in DEFAULT_FILES {
+        if !files.iter().any(|f| f == file) && day_file(day, file).exists() {
+            files.push(file.to_string());
+        }
+    }
+
+    let mut outcomes = Vec::new();
+    for file in files {
+        let input = Input::File(day_file(day, &file));
+        for part in Part::ALL {
+            let expected = answers.expected(day.day, &file, part);
+            let status = match (day.solve(part, &input), expected) {
+                (Ok(actual), Some(expected)) if actual == expected => Status::Pass,
+                (Ok(actual), Some(expected)) => Status::Fail {
+                    expected: expected.to_string(),
+                    actual,
+                },
+                (Ok(actual), None) => Status::Missing { actual },
+                // Parts that are not solved yet and have no recorded answer are not reported.
+                (Err(Error::UnsolvedPart { .. }), None) => continue,
+                (Err(error), _) => Status::Error(error),
+            };
+            outcomes.push(Outcome {
+                day: day.day,
+                part,
+                file: file.clone(),
+                status,
+            });
+        }
+    }
+    outcomes
+}
+
+fn day_file(day: &Day, file: &str) -> PathBuf {
+    Path::new(day.files_dir).join(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse(
+            r#"
+            [day-2."input.txt"]
+            part-1 = "2476"
+
+            [day-2."test.txt"]
+            part-1 = "8"
+            part-2 = "2286"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(answers.expected(2, "input.txt", Part::One), Some("2476"));
+        assert_eq!(answers.expected(2, "input.txt", Part::Two), None);
+        assert_eq!(answers.expected(2, "test.txt", Part::Two), Some("2286"));
+        assert_eq!(answers.expected(3, "test.txt", Part::One), None);
+    }
+
+    #[test]
+    fn test_parse_answers_rejects_bad_day() {
+        assert!(Answers::parse(r#"[day-x."input.txt"]"#).is_err());
+    }
+}
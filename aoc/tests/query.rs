@@ -0,0 +1,35 @@
+use std::process::Command;
+
+fn query(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .arg("query")
+        .args(args)
+        .output()
+        .expect("aoc");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("UTF-8 output")
+}
+
+#[test]
+fn test_query_ordered_and_limited() {
+    let possible = "red <= 12 and green <= 13 and blue <= 14";
+    let args = [possible, "--example", "--order-by", "power"];
+    assert_eq!(query(&args), "2\t12\n5\t36\n1\t48\n");
+
+    let args = [
+        possible,
+        "--example",
+        "--order-by",
+        "power",
+        "--descending",
+        "--limit",
+        "2",
+    ];
+    assert_eq!(query(&args), "1\t48\n5\t36\n");
+
+    assert_eq!(query(&[possible, "--example", "--limit", "2"]), "1\n2\n");
+}
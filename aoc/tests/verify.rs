@@ -0,0 +1,14 @@
+use aoc::verify::{verify, Answers, ANSWERS_PATH};
+
+#[test]
+fn test_recorded_answers() {
+    let answers = Answers::load(ANSWERS_PATH).expect("answers");
+    let outcomes = verify(&answers);
+
+    let failures = outcomes
+        .iter()
+        .filter(|outcome| !outcome.status.is_ok())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::Path;
+
+use aoc_common::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day_1::{parse_u8, Day1, MatchMode};
+
+/// The per-offset `DigitBuffer` scanner that the automaton replaced, kept here as a baseline.
+mod trie_scanner {
+    use std::collections::HashMap;
+
+    use lazy_static::lazy_static;
+    use trie_rs::{Trie, TrieBuilder};
+
+    lazy_static! {
+        static ref DIGIT_TRIE: Trie<u8> = {
+            let mut builder = TrieBuilder::new();
+            for pattern in [
+                "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+                "0", "1", "2", "3", "4", "5", "6", "7", "8", "9",
+            ] {
+                builder.push(pattern);
+            }
+            builder.build()
+        };
+    }
+
+    struct DigitBuffer {
+        start_index: usize,
+    }
+
+    #[derive(Clone, Copy)]
+    enum DigitState {
+        Partial,
+        Invalid,
+        Match(char),
+    }
+
+    impl DigitBuffer {
+        fn advance_index(&mut self, index: usize, string: &str) -> DigitState {
+            let slice = &string[self.start_index..index + 1];
+
+            if DIGIT_TRIE.exact_match(slice) {
+                DigitState::Match(normalize_digit(slice))
+            } else if DIGIT_TRIE.predictive_search(slice).is_empty() {
+                DigitState::Invalid
+            } else {
+                DigitState::Partial
+            }
+        }
+    }
+
+    fn normalize_digit(string: &str) -> char {
+        match string {
+            "zero" | "0" => '0',
+            "one" | "1" => '1',
+            "two" | "2" => '2',
+            "three" | "3" => '3',
+            "four" | "4" => '4',
+            "five" | "5" => '5',
+            "six" | "6" => '6',
+            "seven" | "7" => '7',
+            "eight" | "8" => '8',
+            "nine" | "9" => '9',
+            _ => panic!("normalize_digit"),
+        }
+    }
+
+    pub fn parse_u8(line: &str) -> u8 {
+        let mut digit_buffers: HashMap<usize, DigitBuffer> = HashMap::new();
+        let mut first: Option<char> = None;
+        let mut last: Option<char> = None;
+
+        for index in 0..line.len() {
+            digit_buffers.insert(index, DigitBuffer { start_index: index });
+
+            let mut digits_to_remove = vec![];
+            for digit_buffer in digit_buffers.values_mut() {
+                let new_state = digit_buffer.advance_index(index, line);
+
+                if let DigitState::Match(digit) = new_state {
+                    if first.is_none() {
+                        first = Some(digit);
+                    }
+                    last = Some(digit);
+                }
+
+                if matches!(new_state, DigitState::Invalid | DigitState::Match(_)) {
+                    digits_to_remove.push(digit_buffer.start_index);
+                }
+            }
+            for digit in digits_to_remove {
+                digit_buffers.remove(&digit);
+            }
+        }
+
+        [first.expect("first"), last.expect("last")]
+            .into_iter()
+            .collect::<String>()
+            .parse()
+            .expect("digits")
+    }
+}
+
+fn bench_scanners(c: &mut Criterion) {
+    let input = fs::read_to_string(Path::new(Day1::FILES_DIR).join("input.txt")).expect("input");
+    let lines = input.lines().collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("day-1-scanner");
+    group.bench_function("trie", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| trie_scanner::parse_u8(black_box(line)) as u64)
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("aho-corasick", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| parse_u8(black_box(line), &MatchMode::Words).expect("digits") as u64)
+                .sum::<u64>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(scanner, bench_scanners);
+criterion_main!(scanner);
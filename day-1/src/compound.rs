@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use aho_corasick::AhoCorasick;
+use lazy_static::lazy_static;
+
+use crate::scanner::Token;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Word {
+    Digit(u32),
+    /// "zero" through "nine".
+    Unit(u32),
+    /// "ten" through "nineteen".
+    Teen(u32),
+    /// "twenty", "thirty" and so on.
+    Tens(u32),
+    Hundred,
+}
+
+static WORDS: [(&str, Word); 39] = [
+    ("0", Word::Digit(0)),
+    ("1", Word::Digit(1)),
+    ("2", Word::Digit(2)),
+    ("3", Word::Digit(3)),
+    ("4", Word::Digit(4)),
+    ("5", Word::Digit(5)),
+    ("6", Word::Digit(6)),
+    ("7", Word::Digit(7)),
+    ("8", Word::Digit(8)),
+    ("9", Word::Digit(9)),
+    ("zero", Word::Unit(0)),
+    ("one", Word::Unit(1)),
+    ("two", Word::Unit(2)),
+    ("three", Word::Unit(3)),
+    ("four", Word::Unit(4)),
+    ("five", Word::Unit(5)),
+    ("six", Word::Unit(6)),
+    ("seven", Word::Unit(7)),
+    ("eight", Word::Unit(8)),
+    ("nine", Word::Unit(9)),
+    ("ten", Word::Teen(10)),
+    ("eleven", Word::Teen(11)),
+    ("twelve", Word::Teen(12)),
+    ("thirteen", Word::Teen(13)),
+    ("fourteen", Word::Teen(14)),
+    ("fifteen", Word::Teen(15)),
+    ("sixteen", Word::Teen(16)),
+    ("seventeen", Word::Teen(17)),
+    ("eighteen", Word::Teen(18)),
+    ("nineteen", Word::Teen(19)),
+    ("twenty", Word::Tens(20)),
+    ("thirty", Word::Tens(30)),
+    ("forty", Word::Tens(40)),
+    ("fifty", Word::Tens(50)),
+    ("sixty", Word::Tens(60)),
+    ("seventy", Word::Tens(70)),
+    ("eighty", Word::Tens(80)),
+    ("ninety", Word::Tens(90)),
+    ("hundred", Word::Hundred),
+];
+
+lazy_static! {
+    static ref WORD_AUTOMATON: AhoCorasick =
+        AhoCorasick::new(WORDS.iter().map(|(pattern, _)| pattern)).expect("word automaton");
+}
+
+/// Word matches found in a line, keyed by start offset, longest match first.
+type Matches = BTreeMap<usize, Vec<(usize, Word)>>;
+
+/// Reads digits and English numerals below a thousand from `line`, in order of where they start.
+///
+/// Words are found with the same overlapping scan as the other modes and then joined into
+/// compounds ("twenty" + "one", optionally separated by a hyphen or space). A match that lies
+/// entirely inside a compound is absorbed by it, so "twentyone" yields 21 alone and "eighteen"
+/// yields 18 rather than 8 and 18, while partial overlaps still count: "eightwo" yields 8 and 2.
+pub fn tokens(line: &str) -> Vec<Token> {
+    let mut matches = Matches::new();
+    for found in WORD_AUTOMATON.find_overlapping_iter(line) {
+        matches
+            .entry(found.start())
+            .or_default()
+            .push((found.end(), WORDS[found.pattern().as_usize()].1));
+    }
+    for words in matches.values_mut() {
+        words.sort_by_key(|&(end, _)| Reverse(end));
+    }
+
+    let mut tokens: Vec<Token> = Vec::new();
+    for &start in matches.keys() {
+        let (end, value) = parse_compound(&matches, line, start);
+        let absorbed = tokens
+            .last()
+            .is_some_and(|previous| previous.span.start <= start && end <= previous.span.end);
+        if !absorbed {
+            tokens.push(Token {
+                span: start..end,
+                value,
+            });
+        }
+    }
+    tokens
+}
+
+/// Parses the longest compound starting at `start`, returning where it ends and its value.
+fn parse_compound(matches: &Matches, line: &str, start: usize) -> (usize, u32) {
+    let (end, word) = matches[&start][0];
+    match word {
+        Word::Digit(value) | Word::Teen(value) => (end, value),
+        Word::Unit(0) => (end, 0),
+        Word::Unit(value) => match next_word(matches, line, end, |word| word == Word::Hundred) {
+            Some((end, _)) => parse_after_hundred(matches, line, end, value * 100),
+            None => (end, value),
+        },
+        Word::Tens(value) => parse_after_tens(matches, line, end, value),
+        Word::Hundred => parse_after_hundred(matches, line, end, 100),
+    }
+}
+
+fn parse_after_tens(matches: &Matches, line: &str, end: usize, value: u32) -> (usize, u32) {
+    match next_word(matches, line, end, is_nonzero_unit) {
+        Some((end, Word::Unit(unit))) => (end, value + unit),
+        _ => (end, value),
+    }
+}
+
+fn parse_after_hundred(matches: &Matches, line: &str, end: usize, value: u32) -> (usize, u32) {
+    let rest = next_word(matches, line, end, |word| {
+        matches!(word, Word::Teen(_) | Word::Tens(_)) || is_nonzero_unit(word)
+    });
+    match rest {
+        Some((end, Word::Teen(teen))) => (end, value + teen),
+        Some((end, Word::Unit(unit))) => (end, value + unit),
+        Some((end, Word::Tens(tens))) => parse_after_tens(matches, line, end, value + tens),
+        _ => (end, value),
+    }
+}
+
+fn is_nonzero_unit(word: Word) -> bool {
+    matches!(word, Word::Unit(value) if value > 0)
+}
+
+/// Finds the longest word accepted by `accept` that starts right at `end`, or after a single
+/// hyphen or space.
+fn next_word<F>(matches: &Matches, line: &str, end: usize, accept: F) -> Option<(usize, Word)>
+where
+    F: Fn(Word) -> bool,
+{
+    let mut starts = vec![end];
+    if matches!(line.as_bytes().get(end), Some(b'-' | b' ')) {
+        starts.push(end + 1);
+    }
+
+    starts.into_iter().find_map(|start| {
+        matches
+            .get(&start)?
+            .iter()
+            .copied()
+            .find(|&(_, word)| accept(word))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn values(line: &str) -> Vec<u32> {
+        tokens(line).into_iter().map(|token| token.value).collect()
+    }
+
+    #[test_case("twentyone",               &[21])]
+    #[test_case("twenty-one",              &[21])]
+    #[test_case("twenty one",              &[21])]
+    #[test_case("eighteen",                &[18])]
+    #[test_case("nineteen",                &[19])]
+    #[test_case("sixty",                   &[60])]
+    #[test_case("onehundred",              &[100])]
+    #[test_case("hundred",                 &[100])]
+    #[test_case("three-hundred-forty-two", &[342])]
+    #[test_case("ninehundredeleven",       &[911])]
+    #[test_case("fivehundredseven",        &[507])]
+    #[test_case("eightwo",                 &[8, 2])]
+    #[test_case("oneight",                 &[1, 8])]
+    #[test_case("twentyzero",              &[20, 0])]
+    #[test_case("zerohundred",             &[0, 100])]
+    #[test_case("4fifty5",                 &[4, 50, 5])]
+    #[test_case("twentyoneight",           &[21, 8])]
+    #[test_case("twenty--one",             &[20, 1])]
+    #[test_case("xyz",                     &[])]
+    fn test_tokens(line: &str, expected: &[u32]) {
+        assert_eq!(values(line), expected);
+    }
+
+    #[test]
+    fn test_tokens_spans() {
+        let result = tokens("a twenty-one b7");
+        assert_eq!(
+            result,
+            vec![
+                Token {
+                    span: 2..12,
+                    value: 21,
+                },
+                Token {
+                    span: 14..15,
+                    value: 7,
+                },
+            ]
+        );
+    }
+}
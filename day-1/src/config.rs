@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use aoc_common::{Error, Result};
+use serde::Deserialize;
+
+use crate::scanner::{Language, NumeralSystem, Vocabulary, VocabularyBuilder};
+
+/// A vocabulary described in a TOML file, for example:
+///
+/// ```toml
+/// languages = ["german", "french"]
+/// numerals = ["devanagari"]
+/// case-insensitive = true
+///
+/// [words]
+/// ein = 1
+/// ```
+#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct VocabularyConfig {
+    pub ascii_digits: bool,
+    pub languages: Vec<Language>,
+    pub numerals: Vec<NumeralSystem>,
+    pub case_insensitive: bool,
+    /// Extra words, mapped to the digit they read as.
+    pub words: BTreeMap<String, u32>,
+}
+
+impl Default for VocabularyConfig {
+    fn default() -> Self {
+        Self {
+            ascii_digits: true,
+            languages: Vec::new(),
+            numerals: Vec::new(),
+            case_insensitive: false,
+            words: BTreeMap::new(),
+        }
+    }
+}
+
+impl VocabularyConfig {
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        toml::from_str(contents)
+            .map_err(|error| Error::InvalidInput(format!("vocabulary config: {error}")))
+    }
+
+    pub fn build(&self) -> Result<Vocabulary> {
+        let mut builder = VocabularyBuilder::new();
+        if self.ascii_digits {
+            builder = builder.ascii_digits();
+        }
+        for &language in &self.languages {
+            builder = builder.language(language);
+        }
+        for &system in &self.numerals {
+            builder = builder.numerals(system);
+        }
+        for (word, &value) in &self.words {
+            let digit = char::from_digit(value, 10).ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "vocabulary config: {word} = {value} is not a digit"
+                ))
+            })?;
+            builder = builder.pattern(word.as_str(), digit);
+        }
+        if self.case_insensitive {
+            builder = builder.case_insensitive();
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = VocabularyConfig::parse(
+            r#"
+            languages = ["german", "spanish"]
+            numerals = ["full-width"]
+            case-insensitive = true
+
+            [words]
+            ein = 1
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            VocabularyConfig {
+                ascii_digits: true,
+                languages: vec![Language::German, Language::Spanish],
+                numerals: vec![NumeralSystem::FullWidth],
+                case_insensitive: true,
+                words: BTreeMap::from([("ein".to_string(), 1)]),
+            }
+        );
+
+        let vocabulary = config.build().unwrap();
+        let result = vocabulary
+            .scan("Ein Hund, DOS gatos, ５ x")
+            .collect::<Vec<_>>();
+        assert_eq!(result, ['1', '2', '5']);
+    }
+
+    #[test]
+    fn test_parse_config_rejects_unknown_language() {
+        assert!(VocabularyConfig::parse(r#"languages = ["klingon"]"#).is_err());
+    }
+
+    #[test]
+    fn test_build_config_rejects_non_digit_words() {
+        let config = VocabularyConfig::parse("[words]\nzehn = 10").unwrap();
+        assert!(config.build().is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use std::io;
+
+use displaydoc::Display;
+use thiserror::Error;
+
+#[derive(Debug, Display, Error)]
+pub enum Error {
+    /// Line {line}: no digits in {content:?}
+    NoDigits { line: usize, content: String },
+    /// Invalid UTF-8 at byte {offset}
+    InvalidUtf8 { offset: usize },
+    /// I/O error: {0}
+    Io(#[from] io::Error),
+}
+
+/// I/O errors compare by kind, which is all a caller can match on.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::NoDigits { line, content },
+                Self::NoDigits {
+                    line: other_line,
+                    content: other_content,
+                },
+            ) => line == other_line && content == other_content,
+            (Self::InvalidUtf8 { offset }, Self::InvalidUtf8 { offset: other }) => offset == other,
+            (Self::Io(error), Self::Io(other)) => error.kind() == other.kind(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Error {}
+
+impl From<Error> for aoc_common::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Io(error) => Self::Io(error),
+            error => Self::InvalidInput(error.to_string()),
+        }
+    }
+}
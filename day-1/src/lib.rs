@@ -0,0 +1,302 @@
+mod compound;
+mod config;
+mod error;
+mod parallel;
+mod report;
+mod scanner;
+mod strategy;
+
+use std::io::{BufRead, Write};
+
+use aoc_common::{input_path, Result, Solution};
+
+pub use config::VocabularyConfig;
+pub use error::Error;
+pub use parallel::{process_bytes, process_file};
+pub use report::{LineReport, ReportFormat, ReportWriter, TokenReport};
+pub use scanner::{
+    Language, MatchMode, NumeralSystem, Token, Tokens, Vocabulary, VocabularyBuilder,
+    VocabularyTokens,
+};
+pub use strategy::{AllDigits, Concatenation, FirstLast, MaxPair, Strategy, TokenList};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const FILES_DIR: &'static str = input_path!("files");
+
+    type Input = Vec<String>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse<R>(reader: R) -> Result<Self::Input>
+    where
+        R: BufRead,
+    {
+        Ok(reader.lines().collect::<std::io::Result<Vec<_>>>()?)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(process_input(input, &MatchMode::Digits, Strictness::Strict)?.total)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(process_input(input, &MatchMode::Words, Strictness::Strict)?.total)
+    }
+}
+
+/// What `process_input` does with a line it cannot read a calibration value from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Strictness {
+    /// Fail on the first bad line.
+    Strict,
+    /// Skip bad lines and report them alongside the total of the good ones.
+    Lenient,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Calibration<T = u64> {
+    pub total: T,
+    /// Lines skipped in lenient mode.
+    pub skipped: Vec<Error>,
+}
+
+/// Sums the calibration values of every line using the default `FirstLast` strategy.
+pub fn process_input<I, S>(
+    lines: I,
+    mode: &MatchMode,
+    strictness: Strictness,
+) -> std::result::Result<Calibration, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    process_input_with(lines, mode, strictness, &FirstLast)
+}
+
+pub fn process_input_with<I, S, St>(
+    lines: I,
+    mode: &MatchMode,
+    strictness: Strictness,
+    strategy: &St,
+) -> std::result::Result<Calibration<St::Total>, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    St: Strategy,
+{
+    process_lines(lines, strictness, strategy, |_, line| {
+        Ok(strategy.extract(mode.tokens(line)))
+    })
+}
+
+/// Like `process_input`, but also writes a `LineReport` for every line, including the ones
+/// without digits, so a wrong total can be traced to the tokens that produced it.
+pub fn process_input_reported<I, S, W>(
+    lines: I,
+    mode: &MatchMode,
+    strictness: Strictness,
+    report: &mut ReportWriter<W>,
+) -> std::result::Result<Calibration, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    W: Write,
+{
+    let calibration = process_lines(lines, strictness, &FirstLast, |line, content| {
+        let tokens = mode.tokens(content).collect::<Vec<_>>();
+        let line_report = LineReport::new(line, content, &tokens);
+        report.write(&line_report)?;
+        Ok(line_report.value)
+    })?;
+    report.flush()?;
+    Ok(calibration)
+}
+
+/// Folds the value `extract` reads from each line, numbered from 1, into a `Calibration`.
+fn process_lines<I, S, St, F>(
+    lines: I,
+    strictness: Strictness,
+    strategy: &St,
+    mut extract: F,
+) -> std::result::Result<Calibration<St::Total>, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    St: Strategy,
+    F: FnMut(usize, &str) -> std::result::Result<Option<St::Output>, Error>,
+{
+    let mut calibration = Calibration::default();
+    for (index, line) in lines.into_iter().enumerate() {
+        let line = line.as_ref();
+        match extract(index + 1, line)? {
+            Some(value) => strategy.accumulate(&mut calibration.total, value),
+            None => skip_line(&mut calibration, strictness, index + 1, line)?,
+        }
+    }
+    Ok(calibration)
+}
+
+fn skip_line<T>(
+    calibration: &mut Calibration<T>,
+    strictness: Strictness,
+    line: usize,
+    content: &str,
+) -> std::result::Result<(), Error> {
+    let error = Error::NoDigits {
+        line,
+        content: content.to_string(),
+    };
+    match strictness {
+        Strictness::Strict => Err(error),
+        Strictness::Lenient => {
+            calibration.skipped.push(error);
+            Ok(())
+        }
+    }
+}
+
+/// Reads the calibration value from the first digit of the first token and the last digit of the
+/// last token on the line, or `None` if the line has no digits.
+pub fn parse_u8(line: &str, mode: &MatchMode) -> Option<u8> {
+    FirstLast.extract(mode.tokens(line))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_bigint::BigUint;
+    use test_case::test_case;
+
+    #[test_case("1abc2", MatchMode::Digits, 12)]
+    #[test_case("pqr3stu8vwx", MatchMode::Digits, 38)]
+    #[test_case("a1b2c3d4e5f", MatchMode::Digits, 15)]
+    #[test_case("treb7uchet", MatchMode::Digits, 77)]
+    #[test_case("two1nine", MatchMode::Digits, 11)]
+    #[test_case("7pqrstsixteen", MatchMode::Digits, 77)]
+    #[test_case("two1nine", MatchMode::Words, 29)]
+    #[test_case("eightwothree", MatchMode::Words, 83)]
+    #[test_case("abcone2threexyz", MatchMode::Words, 13)]
+    #[test_case("xtwone3four", MatchMode::Words, 24)]
+    #[test_case("4nineeightseven2", MatchMode::Words, 42)]
+    #[test_case("zoneight234", MatchMode::Words, 14)]
+    #[test_case("7pqrstsixteen", MatchMode::Words, 76)]
+    #[test_case("7pqrstsixteen", MatchMode::Compound, 76)]
+    #[test_case("twentyone", MatchMode::Compound, 21)]
+    #[test_case("x4ninety-nine", MatchMode::Compound, 49)]
+    #[test_case("eighteen", MatchMode::Compound, 18)]
+    #[test_case("threehundredten", MatchMode::Compound, 30)]
+    fn test_parse_u8(line: &str, mode: MatchMode, expected: u8) {
+        let result = parse_u8(line, &mode).expect("ok");
+        assert_eq!(result, expected);
+    }
+
+    #[test_case("٤ and ٢ cubes", 42 ; "arabic indic")]
+    #[test_case("ｎ７x३",        73 ; "full width and devanagari")]
+    #[test_case("über1drei",     11 ; "latin with diacritics")]
+    fn test_parse_u8_mixed_script(line: &str, expected: u8) {
+        let mode = MatchMode::Custom(
+            Vocabulary::builder()
+                .ascii_digits()
+                .numerals(NumeralSystem::FullWidth)
+                .numerals(NumeralSystem::ArabicIndic)
+                .numerals(NumeralSystem::Devanagari)
+                .build()
+                .unwrap(),
+        );
+        let result = parse_u8(line, &mode).expect("ok");
+        assert_eq!(result, expected);
+    }
+
+    #[test_case("abcd",   42)]
+    #[test_case("bcabcd", 22)]
+    fn test_parse_u8_nested_patterns(line: &str, expected: u8) {
+        let mode = MatchMode::Custom(Vocabulary::new([("abcd", '4'), ("bc", '2')]).unwrap());
+        assert_eq!(parse_u8(line, &mode), Some(expected));
+    }
+
+    #[test]
+    fn test_process_input_with_concatenation() {
+        let lines = ["1abc2", "pqr3stu8vwx", "a1b2c3d4e5f", "treb7uchet"];
+        let result = process_input_with(
+            lines,
+            &MatchMode::Digits,
+            Strictness::Strict,
+            &Concatenation,
+        )
+        .unwrap();
+        assert_eq!(result.total, BigUint::from(12u32 + 38 + 12345 + 7));
+    }
+
+    #[test]
+    fn test_process_input_reported() {
+        let lines = ["two1nine", "abc", "zoneight234"];
+        let mut output = Vec::new();
+        let mut report = ReportWriter::new(&mut output, ReportFormat::Csv);
+        let result =
+            process_input_reported(lines, &MatchMode::Words, Strictness::Lenient, &mut report)
+                .unwrap();
+        drop(report);
+        assert_eq!(result.total, 29 + 14);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "line,tokens,first,last,value\n",
+                "1,0..3:2 3..4:1 4..8:9,2,9,29\n",
+                "2,,,,\n",
+                "3,1..4:1 3..8:8 8..9:2 9..10:3 10..11:4,1,4,14\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_process_input_reported_strict() {
+        let lines = ["two1nine", "abc", "zoneight234"];
+        let mut output = Vec::new();
+        let mut report = ReportWriter::new(&mut output, ReportFormat::JsonLines);
+        let result =
+            process_input_reported(lines, &MatchMode::Words, Strictness::Strict, &mut report);
+        assert_eq!(
+            result,
+            Err(Error::NoDigits {
+                line: 2,
+                content: "abc".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_input_strict() {
+        let lines = ["1abc2", "pqr3stu8vwx", "abcdef", "treb7uchet"];
+        let result = process_input(lines, &MatchMode::Digits, Strictness::Strict);
+        assert_eq!(
+            result,
+            Err(Error::NoDigits {
+                line: 3,
+                content: "abcdef".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_input_lenient() {
+        let lines = ["1abc2", "two", "pqr3stu8vwx", "abcdef", "treb7uchet"];
+        let result = process_input(lines, &MatchMode::Digits, Strictness::Lenient).unwrap();
+        assert_eq!(result.total, 12 + 38 + 77);
+        assert_eq!(
+            result.skipped,
+            vec![
+                Error::NoDigits {
+                    line: 2,
+                    content: "two".to_string(),
+                },
+                Error::NoDigits {
+                    line: 4,
+                    content: "abcdef".to_string(),
+                },
+            ]
+        );
+    }
+}
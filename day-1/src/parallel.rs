@@ -0,0 +1,297 @@
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+use rayon::prelude::*;
+
+use crate::scanner::MatchMode;
+use crate::strategy::{FirstLast, Strategy};
+use crate::{Calibration, Error, Strictness};
+
+/// Chunks smaller than this are not worth handing to another thread.
+const MIN_CHUNK_LEN: usize = 1 << 20;
+
+/// Memory-maps the file at `path` and sums its calibration values across all threads of the
+/// rayon pool. Gives the same result as `process_input` on the file's lines.
+pub fn process_file<P>(
+    path: P,
+    mode: &MatchMode,
+    strictness: Strictness,
+) -> Result<Calibration<u128>, Error>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    // SAFETY: the map is only read, and the file is not expected to change while it is being
+    // processed. A concurrent writer can make the result meaningless but not unsound for `[u8]`.
+    let mmap = unsafe { Mmap::map(&file)? };
+    process_bytes(&mmap, mode, strictness)
+}
+
+/// Splits `bytes` into chunks on line boundaries and processes the chunks in parallel.
+pub fn process_bytes(
+    bytes: &[u8],
+    mode: &MatchMode,
+    strictness: Strictness,
+) -> Result<Calibration<u128>, Error> {
+    let chunk_len = (bytes.len() / rayon::current_num_threads().max(1)).max(MIN_CHUNK_LEN);
+    process_chunks(bytes, chunk_len, mode, strictness)
+}
+
+fn process_chunks(
+    bytes: &[u8],
+    chunk_len: usize,
+    mode: &MatchMode,
+    strictness: Strictness,
+) -> Result<Calibration<u128>, Error> {
+    let chunks = split_lines(bytes, chunk_len);
+    let processed = chunks
+        .par_iter()
+        .enumerate()
+        .map(|(index, chunk)| process_chunk(index, chunk, mode, strictness))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|failed| first_error(&chunks, failed, mode, strictness))?;
+
+    let mut calibration = Calibration::default();
+    let mut offset = 0;
+    for chunk in processed {
+        calibration.total += chunk.total;
+        for (line, content) in chunk.skipped {
+            calibration.skipped.push(Error::NoDigits {
+                line: offset + line,
+                content,
+            });
+        }
+        offset += chunk.lines;
+    }
+    Ok(calibration)
+}
+
+struct Chunk {
+    lines: usize,
+    total: u128,
+    /// Lines without digits, numbered from 1 within the chunk.
+    skipped: Vec<(usize, String)>,
+}
+
+/// A chunk that failed, with its error's line or byte counted from the start of the chunk.
+struct ChunkError {
+    chunk: usize,
+    error: Error,
+}
+
+/// Processes one chunk, stopping at its first bad line in strict mode.
+fn process_chunk(
+    index: usize,
+    bytes: &[u8],
+    mode: &MatchMode,
+    strictness: Strictness,
+) -> Result<Chunk, ChunkError> {
+    let fail = |error| ChunkError {
+        chunk: index,
+        error,
+    };
+    // Lines before invalid UTF-8 are still scanned, so a bad line before it is reported first.
+    let (text, invalid) = match std::str::from_utf8(bytes) {
+        Ok(text) => (text, None),
+        Err(error) => {
+            let valid = &bytes[..error.valid_up_to()];
+            let lines = valid
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(&valid[..0], |newline| &valid[..newline + 1]);
+            let text = std::str::from_utf8(lines).expect("valid UTF-8 prefix");
+            (text, Some(error.valid_up_to()))
+        }
+    };
+    let mut chunk = Chunk {
+        lines: 0,
+        total: 0,
+        skipped: Vec::new(),
+    };
+    for line in text.lines() {
+        chunk.lines += 1;
+        match (FirstLast.extract(mode.tokens(line)), strictness) {
+            (Some(value), _) => chunk.total += value as u128,
+            (None, Strictness::Strict) => {
+                return Err(fail(Error::NoDigits {
+                    line: chunk.lines,
+                    content: line.to_string(),
+                }));
+            }
+            (None, Strictness::Lenient) => chunk.skipped.push((chunk.lines, line.to_string())),
+        }
+    }
+    match invalid {
+        Some(offset) => Err(fail(Error::InvalidUtf8 { offset })),
+        None => Ok(chunk),
+    }
+}
+
+/// The document's first error. Chunks stop as soon as any of them fails, which need not be the
+/// earliest, so the chunks before `failed` are checked again for an earlier one.
+fn first_error(
+    chunks: &[&[u8]],
+    failed: ChunkError,
+    mode: &MatchMode,
+    strictness: Strictness,
+) -> Error {
+    let first = chunks[..failed.chunk]
+        .par_iter()
+        .enumerate()
+        .find_map_first(|(index, chunk)| process_chunk(index, chunk, mode, strictness).err())
+        .unwrap_or(failed);
+
+    // Every chunk before the failed one ends in a newline, so its lines are its newlines.
+    let before = &chunks[..first.chunk];
+    match first.error {
+        Error::NoDigits { line, content } => Error::NoDigits {
+            line: before
+                .iter()
+                .map(|chunk| chunk.iter().filter(|&&b| b == b'\n').count())
+                .sum::<usize>()
+                + line,
+            content,
+        },
+        Error::InvalidUtf8 { offset } => Error::InvalidUtf8 {
+            offset: before.iter().map(|chunk| chunk.len()).sum::<usize>() + offset,
+        },
+        error => error,
+    }
+}
+
+/// Cuts `bytes` into pieces of roughly `chunk_len` bytes, each ending just after a newline (or at
+/// the end of the input), so that no line is split between two pieces.
+fn split_lines(bytes: &[u8], chunk_len: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let end = match rest.get(chunk_len..) {
+            Some(tail) => tail
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(rest.len(), |newline| chunk_len + newline + 1),
+            None => rest.len(),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_input;
+    use std::io::Write;
+    use test_case::test_case;
+
+    const DOCUMENT: &str = "two1nine\r\neightwothree\nabc\nxtwone3four\n\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+
+    #[test_case(1)]
+    #[test_case(3)]
+    #[test_case(16)]
+    #[test_case(1024)]
+    fn test_split_lines(chunk_len: usize) {
+        let chunks = split_lines(DOCUMENT.as_bytes(), chunk_len);
+        assert_eq!(chunks.concat(), DOCUMENT.as_bytes());
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert_eq!(chunk.last(), Some(&b'\n'));
+        }
+    }
+
+    #[test_case(1)]
+    #[test_case(8)]
+    #[test_case(MIN_CHUNK_LEN)]
+    fn test_matches_sequential(chunk_len: usize) {
+        let lines = DOCUMENT.lines().collect::<Vec<_>>();
+        let sequential = process_input(&lines, &MatchMode::Words, Strictness::Lenient).unwrap();
+
+        let parallel = process_chunks(
+            DOCUMENT.as_bytes(),
+            chunk_len,
+            &MatchMode::Words,
+            Strictness::Lenient,
+        )
+        .unwrap();
+        assert_eq!(parallel.total, sequential.total as u128);
+        assert_eq!(parallel.skipped, sequential.skipped);
+    }
+
+    #[test]
+    fn test_process_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for _ in 0..10_000 {
+            writeln!(file, "{DOCUMENT}").unwrap();
+        }
+        let lines = DOCUMENT.lines().collect::<Vec<_>>();
+        let sequential = process_input(&lines, &MatchMode::Words, Strictness::Lenient).unwrap();
+
+        let result = process_file(file.path(), &MatchMode::Words, Strictness::Lenient).unwrap();
+        assert_eq!(result.total, sequential.total as u128 * 10_000);
+        assert_eq!(result.skipped.len(), sequential.skipped.len() * 10_000);
+        assert_eq!(
+            result.skipped.last(),
+            Some(&Error::NoDigits {
+                line: 10_000 * lines.len() - 3,
+                content: "".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_process_file_strict() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "1abc2\nnone\n7").unwrap();
+        let result = process_file(file.path(), &MatchMode::Digits, Strictness::Strict);
+        assert_eq!(
+            result,
+            Err(Error::NoDigits {
+                line: 2,
+                content: "none".to_string(),
+            })
+        );
+    }
+
+    #[test_case(1)]
+    #[test_case(8)]
+    #[test_case(MIN_CHUNK_LEN)]
+    fn test_strict_reports_first_bad_line(chunk_len: usize) {
+        let result = process_chunks(
+            DOCUMENT.as_bytes(),
+            chunk_len,
+            &MatchMode::Digits,
+            Strictness::Strict,
+        );
+        assert_eq!(
+            result,
+            Err(Error::NoDigits {
+                line: 2,
+                content: "eightwothree".to_string(),
+            })
+        );
+    }
+
+    #[test_case(1)]
+    #[test_case(MIN_CHUNK_LEN)]
+    fn test_invalid_utf8(chunk_len: usize) {
+        let bytes = b"1abc2\nx\xffy3\n";
+        let result = process_chunks(bytes, chunk_len, &MatchMode::Digits, Strictness::Lenient);
+        assert_eq!(result, Err(Error::InvalidUtf8 { offset: 7 }));
+    }
+
+    #[test_case(1)]
+    #[test_case(MIN_CHUNK_LEN)]
+    fn test_strict_bad_line_before_invalid_utf8(chunk_len: usize) {
+        let bytes = b"1abc2\nnone\nx\xffy3\n";
+        let result = process_chunks(bytes, chunk_len, &MatchMode::Digits, Strictness::Strict);
+        assert_eq!(
+            result,
+            Err(Error::NoDigits {
+                line: 2,
+                content: "none".to_string(),
+            })
+        );
+    }
+}
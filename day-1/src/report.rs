@@ -0,0 +1,202 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::scanner::Token;
+use crate::strategy::FirstLast;
+
+/// How a `ReportWriter` lays out its records.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReportFormat {
+    /// One JSON object per line.
+    JsonLines,
+    /// One row per line, with the tokens packed into a single column as `start..end:value`.
+    Csv,
+}
+
+impl FromStr for ReportFormat {
+    type Err = aoc_common::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jsonl" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            _ => Err(aoc_common::Error::InvalidArgument(format!(
+                "report format {s:?}, expected jsonl or csv"
+            ))),
+        }
+    }
+}
+
+/// What the scanner saw on one calibration line.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct LineReport {
+    /// 1-based line number.
+    pub line: usize,
+    pub tokens: Vec<TokenReport>,
+    pub first: Option<u8>,
+    pub last: Option<u8>,
+    pub value: Option<u8>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TokenReport {
+    pub start: usize,
+    pub end: usize,
+    /// The matched text, as written in the line.
+    pub text: String,
+    /// The number the text reads as.
+    pub value: u32,
+}
+
+impl LineReport {
+    pub fn new(line: usize, content: &str, tokens: &[Token]) -> Self {
+        let digits = FirstLast::digits(tokens.iter().cloned());
+        Self {
+            line,
+            tokens: tokens
+                .iter()
+                .map(|token| TokenReport {
+                    start: token.span.start,
+                    end: token.span.end,
+                    text: content[token.span.clone()].to_string(),
+                    value: token.value,
+                })
+                .collect(),
+            first: digits.map(|(first, _)| first),
+            last: digits.map(|(_, last)| last),
+            value: digits.map(FirstLast::value),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CsvRecord {
+    line: usize,
+    tokens: String,
+    first: Option<u8>,
+    last: Option<u8>,
+    value: Option<u8>,
+}
+
+impl From<&LineReport> for CsvRecord {
+    fn from(report: &LineReport) -> Self {
+        let tokens = report
+            .tokens
+            .iter()
+            .map(|token| format!("{}..{}:{}", token.start, token.end, token.value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            line: report.line,
+            tokens,
+            first: report.first,
+            last: report.last,
+            value: report.value,
+        }
+    }
+}
+
+enum Sink<W: Write> {
+    JsonLines(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+/// Writes `LineReport`s to any writer in the chosen format.
+pub struct ReportWriter<W: Write> {
+    sink: Sink<W>,
+}
+
+impl<W: Write> ReportWriter<W> {
+    pub fn new(writer: W, format: ReportFormat) -> Self {
+        let sink = match format {
+            ReportFormat::JsonLines => Sink::JsonLines(writer),
+            ReportFormat::Csv => Sink::Csv(Box::new(csv::Writer::from_writer(writer))),
+        };
+        Self { sink }
+    }
+
+    pub fn write(&mut self, report: &LineReport) -> io::Result<()> {
+        match &mut self.sink {
+            Sink::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, report).map_err(io::Error::from)?;
+                writer.write_all(b"\n")?;
+            }
+            Sink::Csv(writer) => writer
+                .serialize(CsvRecord::from(report))
+                .map_err(io::Error::from)?,
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.sink {
+            Sink::JsonLines(writer) => writer.flush()?,
+            Sink::Csv(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::MatchMode;
+
+    fn render(format: ReportFormat, lines: &[&str]) -> String {
+        let mut output = Vec::new();
+        let mut writer = ReportWriter::new(&mut output, format);
+        for (index, line) in lines.iter().enumerate() {
+            let tokens = MatchMode::Words.tokens(line).collect::<Vec<_>>();
+            writer
+                .write(&LineReport::new(index + 1, line, &tokens))
+                .unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_line_report() {
+        let line = "xtwone3";
+        let tokens = MatchMode::Words.tokens(line).collect::<Vec<_>>();
+        let report = LineReport::new(4, line, &tokens);
+        assert_eq!(report.first, Some(2));
+        assert_eq!(report.last, Some(3));
+        assert_eq!(report.value, Some(23));
+        assert_eq!(
+            report
+                .tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<Vec<_>>(),
+            ["two", "one", "3"]
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let output = render(ReportFormat::JsonLines, &["a1two", "abc"]);
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"line":1,"tokens":[{"start":1,"end":2,"text":"1","value":1},"#,
+                r#"{"start":2,"end":5,"text":"two","value":2}],"first":1,"last":2,"value":12}"#,
+                "\n",
+                r#"{"line":2,"tokens":[],"first":null,"last":null,"value":null}"#,
+                "\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let output = render(ReportFormat::Csv, &["a1two", "abc"]);
+        assert_eq!(
+            output,
+            "line,tokens,first,last,value\n1,1..2:1 2..5:2,1,2,12\n2,,,,\n"
+        );
+    }
+}
@@ -0,0 +1,616 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::Range;
+use std::vec;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, FindOverlappingIter, MatchKind};
+use aoc_common::{Error, Result};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::compound;
+
+static ASCII_DIGITS: [(&str, char); 10] = [
+    ("0", '0'),
+    ("1", '1'),
+    ("2", '2'),
+    ("3", '3'),
+    ("4", '4'),
+    ("5", '5'),
+    ("6", '6'),
+    ("7", '7'),
+    ("8", '8'),
+    ("9", '9'),
+];
+
+static ENGLISH_DIGITS: [(&str, char); 10] = [
+    ("zero", '0'),
+    ("one", '1'),
+    ("two", '2'),
+    ("three", '3'),
+    ("four", '4'),
+    ("five", '5'),
+    ("six", '6'),
+    ("seven", '7'),
+    ("eight", '8'),
+    ("nine", '9'),
+];
+
+static GERMAN_DIGITS: [(&str, char); 10] = [
+    ("null", '0'),
+    ("eins", '1'),
+    ("zwei", '2'),
+    ("drei", '3'),
+    ("vier", '4'),
+    ("fünf", '5'),
+    ("sechs", '6'),
+    ("sieben", '7'),
+    ("acht", '8'),
+    ("neun", '9'),
+];
+
+static FRENCH_DIGITS: [(&str, char); 10] = [
+    ("zéro", '0'),
+    ("un", '1'),
+    ("deux", '2'),
+    ("trois", '3'),
+    ("quatre", '4'),
+    ("cinq", '5'),
+    ("six", '6'),
+    ("sept", '7'),
+    ("huit", '8'),
+    ("neuf", '9'),
+];
+
+static SPANISH_DIGITS: [(&str, char); 10] = [
+    ("cero", '0'),
+    ("uno", '1'),
+    ("dos", '2'),
+    ("tres", '3'),
+    ("cuatro", '4'),
+    ("cinco", '5'),
+    ("seis", '6'),
+    ("siete", '7'),
+    ("ocho", '8'),
+    ("nueve", '9'),
+];
+
+lazy_static! {
+    static ref DIGITS_VOCABULARY: Vocabulary = VocabularyBuilder::new()
+        .ascii_digits()
+        .build()
+        .expect("digits vocabulary");
+    static ref WORDS_VOCABULARY: Vocabulary = VocabularyBuilder::new()
+        .ascii_digits()
+        .english()
+        .build()
+        .expect("words vocabulary");
+}
+
+/// Languages with built-in spelled-out digit words.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+impl Language {
+    pub fn words(&self) -> &'static [(&'static str, char); 10] {
+        match self {
+            Self::English => &ENGLISH_DIGITS,
+            Self::German => &GERMAN_DIGITS,
+            Self::French => &FRENCH_DIGITS,
+            Self::Spanish => &SPANISH_DIGITS,
+        }
+    }
+}
+
+/// Decimal digit sets outside ASCII that can be recognized as digits.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NumeralSystem {
+    /// `０`-`９`, as used in CJK text.
+    FullWidth,
+    /// `٠`-`٩`.
+    ArabicIndic,
+    /// `۰`-`۹`, as used in Persian and Urdu.
+    ExtendedArabicIndic,
+    /// `०`-`९`.
+    Devanagari,
+}
+
+impl NumeralSystem {
+    fn zero(&self) -> u32 {
+        match self {
+            Self::FullWidth => 0xFF10,
+            Self::ArabicIndic => 0x0660,
+            Self::ExtendedArabicIndic => 0x06F0,
+            Self::Devanagari => 0x0966,
+        }
+    }
+
+    /// Every numeral in the system, with the ASCII digit it normalizes to.
+    pub fn numerals(&self) -> impl Iterator<Item = (char, char)> {
+        let zero = self.zero();
+        (0..10).map(move |value| {
+            let numeral = char::from_u32(zero + value).expect("numeral code point");
+            let digit = char::from_digit(value, 10).expect("decimal digit");
+            (numeral, digit)
+        })
+    }
+}
+
+/// Which patterns count as digits when scanning a calibration line.
+#[derive(Clone, Debug)]
+pub enum MatchMode {
+    /// ASCII digits only, as in part 1.
+    Digits,
+    /// ASCII digits and spelled-out English digits, as in part 2.
+    Words,
+    /// ASCII digits and English numerals below a thousand, so "twenty-one" reads as 21.
+    Compound,
+    Custom(Vocabulary),
+}
+
+impl MatchMode {
+    pub fn tokens<'a>(&'a self, line: &'a str) -> Tokens<'a> {
+        match self {
+            Self::Digits => Tokens::Vocabulary(DIGITS_VOCABULARY.tokens(line)),
+            Self::Words => Tokens::Vocabulary(WORDS_VOCABULARY.tokens(line)),
+            Self::Compound => Tokens::Compound(compound::tokens(line).into_iter()),
+            Self::Custom(vocabulary) => Tokens::Vocabulary(vocabulary.tokens(line)),
+        }
+    }
+}
+
+/// A number read from a calibration line: a single digit, or a compound numeral such as
+/// "twenty-one".
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    /// Byte range of the matched text within the line.
+    pub span: Range<usize>,
+    pub value: u32,
+}
+
+impl Token {
+    pub fn first_digit(&self) -> u8 {
+        let mut value = self.value;
+        while value >= 10 {
+            value /= 10;
+        }
+        value as u8
+    }
+
+    pub fn last_digit(&self) -> u8 {
+        (self.value % 10) as u8
+    }
+
+    /// The decimal digits of the token's value, most significant first.
+    pub fn digits(&self) -> impl Iterator<Item = u8> {
+        self.value
+            .to_string()
+            .into_bytes()
+            .into_iter()
+            .map(|b| b - b'0')
+    }
+}
+
+pub enum Tokens<'a> {
+    Vocabulary(VocabularyTokens<'a>),
+    Compound(vec::IntoIter<Token>),
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Vocabulary(tokens) => tokens.next(),
+            Self::Compound(tokens) => tokens.next(),
+        }
+    }
+}
+
+/// The tokens a `Vocabulary` found in one line, in the order they start.
+///
+/// The automaton reports overlapping matches in the order they end, which is also the order they
+/// start unless one pattern contains another. Only then are matches held back, until no later
+/// match can start before them.
+pub struct VocabularyTokens<'a> {
+    vocabulary: &'a Vocabulary,
+    matches: FindOverlappingIter<'a, 'a>,
+    /// Matches held back, as (start, end, pattern id).
+    pending: BinaryHeap<Reverse<(usize, usize, usize)>>,
+    /// The end of the last match the automaton reported.
+    end: usize,
+}
+
+impl VocabularyTokens<'_> {
+    fn token(&self, (start, end, pattern): (usize, usize, usize)) -> Token {
+        Token {
+            span: start..end,
+            value: self.vocabulary.digits[pattern]
+                .to_digit(10)
+                .expect("vocabulary digit"),
+        }
+    }
+
+    /// Whether every match still to come starts after `start`, or is no shorter if it starts
+    /// there.
+    fn settled(&self, start: usize) -> bool {
+        start + self.vocabulary.longest <= self.end
+    }
+}
+
+impl Iterator for VocabularyTokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&Reverse(first)) = self.pending.peek() {
+                if self.settled(first.0) {
+                    self.pending.pop();
+                    return Some(self.token(first));
+                }
+            }
+            let Some(found) = self.matches.next() else {
+                let Reverse(first) = self.pending.pop()?;
+                return Some(self.token(first));
+            };
+            let found = (found.start(), found.end(), found.pattern().as_usize());
+            if !self.vocabulary.nested {
+                return Some(self.token(found));
+            }
+            self.end = found.1;
+            self.pending.push(Reverse(found));
+        }
+    }
+}
+
+/// A set of patterns that read as digits, compiled into a single automaton.
+#[derive(Clone, Debug)]
+pub struct Vocabulary {
+    automaton: AhoCorasick,
+    /// The digit each pattern normalizes to, indexed by pattern id.
+    digits: Vec<char>,
+    /// The length in bytes of the longest pattern.
+    longest: usize,
+    /// Whether some pattern contains another, so matches can end out of the order they start.
+    nested: bool,
+}
+
+impl Vocabulary {
+    pub fn builder() -> VocabularyBuilder {
+        VocabularyBuilder::new()
+    }
+
+    pub fn new<I, P>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (P, char)>,
+        P: AsRef<str>,
+    {
+        Self::compile(patterns, false)
+    }
+
+    fn compile<I, P>(patterns: I, case_insensitive: bool) -> Result<Self>
+    where
+        I: IntoIterator<Item = (P, char)>,
+        P: AsRef<str>,
+    {
+        let mut expanded: Vec<String> = Vec::new();
+        let mut digits = Vec::new();
+        for (pattern, digit) in patterns {
+            if !digit.is_ascii_digit() {
+                return Err(Error::InvalidInput(format!(
+                    "vocabulary maps to non-digit {digit:?}"
+                )));
+            }
+
+            let pattern = pattern.as_ref();
+            if !case_insensitive {
+                expanded.push(pattern.to_string());
+                digits.push(digit);
+                continue;
+            }
+
+            // Patterns that only differ in case would match the same text twice.
+            let folded = pattern.to_lowercase();
+            if expanded
+                .iter()
+                .any(|existing| existing.to_lowercase() == folded)
+            {
+                continue;
+            }
+            for variant in case_variants(pattern) {
+                expanded.push(variant);
+                digits.push(digit);
+            }
+        }
+
+        let automaton = AhoCorasickBuilder::new()
+            .match_kind(MatchKind::Standard)
+            .ascii_case_insensitive(case_insensitive)
+            .build(&expanded)
+            .map_err(|error| Error::InvalidInput(format!("vocabulary: {error}")))?;
+
+        let longest = expanded.iter().map(String::len).max().unwrap_or(0);
+        let folded = expanded
+            .iter()
+            .map(|pattern| {
+                if case_insensitive {
+                    pattern.to_lowercase()
+                } else {
+                    pattern.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+        let nested = folded.iter().any(|outer| {
+            folded
+                .iter()
+                .any(|inner| inner.len() < outer.len() && outer.contains(inner.as_str()))
+        });
+        Ok(Self {
+            automaton,
+            digits,
+            longest,
+            nested,
+        })
+    }
+
+    /// Yields every digit in `line`, in the order the matches start. Matches may overlap, so
+    /// "eightwo" yields both '8' and '2', and a pattern inside another, such as "bc" in "abcd",
+    /// comes after it. Every pattern is valid UTF-8, so matches always fall on character
+    /// boundaries whatever script the rest of the line is in.
+    pub fn scan<'a>(&'a self, line: &'a str) -> impl Iterator<Item = char> + 'a {
+        self.tokens(line)
+            .map(|token| char::from_digit(token.value, 10).expect("vocabulary digit"))
+    }
+
+    /// Like `scan`, but keeps where in the line each digit was found. Matches with the same start
+    /// come shortest first.
+    pub fn tokens<'a>(&'a self, line: &'a str) -> VocabularyTokens<'a> {
+        VocabularyTokens {
+            vocabulary: self,
+            matches: self.automaton.find_overlapping_iter(line),
+            pending: BinaryHeap::new(),
+            end: 0,
+        }
+    }
+}
+
+/// Every spelling of `pattern` with its non-ASCII letters in either case. ASCII letters are left
+/// to the automaton's own case folding, so a pattern expands to one variant per combination of
+/// cased non-ASCII letters, which is rarely more than two.
+fn case_variants(pattern: &str) -> Vec<String> {
+    pattern.chars().fold(vec![String::new()], |variants, c| {
+        let mut spellings = vec![c.to_string()];
+        if !c.is_ascii() {
+            for spelling in [c.to_lowercase().to_string(), c.to_uppercase().to_string()] {
+                if !spellings.contains(&spelling) {
+                    spellings.push(spelling);
+                }
+            }
+        }
+
+        variants
+            .iter()
+            .flat_map(|variant| {
+                spellings
+                    .iter()
+                    .map(move |spelling| format!("{variant}{spelling}"))
+            })
+            .collect()
+    })
+}
+
+/// Assembles a `Vocabulary` from the built-in pattern sets and custom patterns. Patterns added
+/// more than once are kept once.
+#[derive(Clone, Debug, Default)]
+pub struct VocabularyBuilder {
+    patterns: Vec<(String, char)>,
+    case_insensitive: bool,
+}
+
+impl VocabularyBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pattern<P>(mut self, pattern: P, digit: char) -> Self
+    where
+        P: Into<String>,
+    {
+        let pattern = pattern.into();
+        if !self
+            .patterns
+            .iter()
+            .any(|(existing, _)| *existing == pattern)
+        {
+            self.patterns.push((pattern, digit));
+        }
+        self
+    }
+
+    pub fn ascii_digits(self) -> Self {
+        ASCII_DIGITS
+            .into_iter()
+            .fold(self, |builder, (pattern, digit)| {
+                builder.pattern(pattern, digit)
+            })
+    }
+
+    pub fn english(self) -> Self {
+        self.language(Language::English)
+    }
+
+    pub fn language(self, language: Language) -> Self {
+        language
+            .words()
+            .iter()
+            .fold(self, |builder, &(pattern, digit)| {
+                builder.pattern(pattern, digit)
+            })
+    }
+
+    /// Matches words regardless of case, including non-ASCII letters such as "FÜNF".
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
+    pub fn numerals(self, system: NumeralSystem) -> Self {
+        system.numerals().fold(self, |builder, (numeral, digit)| {
+            builder.pattern(numeral.to_string(), digit)
+        })
+    }
+
+    pub fn build(self) -> Result<Vocabulary> {
+        Vocabulary::compile(self.patterns, self.case_insensitive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("eightwo",  MatchMode::Words,  &['8', '2'])]
+    #[test_case("oneight",  MatchMode::Words,  &['1', '8'])]
+    #[test_case("twone3",   MatchMode::Words,  &['2', '1', '3'])]
+    #[test_case("sevenine", MatchMode::Words,  &['7', '9'])]
+    #[test_case("twone3",   MatchMode::Digits, &['3'])]
+    #[test_case("abc",      MatchMode::Words,  &[])]
+    fn test_scan(line: &str, mode: MatchMode, expected: &[char]) {
+        let result = mode
+            .tokens(line)
+            .map(|token| char::from_digit(token.value, 10).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_tokens_spans() {
+        let result = MatchMode::Words.tokens("xeightwo7").collect::<Vec<_>>();
+        assert_eq!(
+            result,
+            vec![
+                Token {
+                    span: 1..6,
+                    value: 8,
+                },
+                Token {
+                    span: 5..8,
+                    value: 2,
+                },
+                Token {
+                    span: 8..9,
+                    value: 7,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokens_nested_patterns() {
+        let vocabulary = Vocabulary::new([("abcd", '4'), ("bc", '2'), ("cd", '3')]).unwrap();
+        let result = vocabulary
+            .tokens("abcdbc")
+            .map(|token| token.span)
+            .collect::<Vec<_>>();
+        assert_eq!(result, [0..4, 1..3, 2..4, 4..6]);
+    }
+
+    #[test_case(7, 7, 7)]
+    #[test_case(21, 2, 1)]
+    #[test_case(140, 1, 0)]
+    fn test_token_digits(value: u32, first: u8, last: u8) {
+        let token = Token { span: 0..1, value };
+        assert_eq!((token.first_digit(), token.last_digit()), (first, last));
+    }
+
+    #[test]
+    fn test_custom_vocabulary() {
+        let vocabulary = Vocabulary::new([("uno", '1'), ("dos", '2'), ("3", '3')]).unwrap();
+        let result = vocabulary.scan("xunodos3one").collect::<Vec<_>>();
+        assert_eq!(result, ['1', '2', '3']);
+    }
+
+    #[test_case("١٢abc",          &['1', '2']           ; "arabic indic")]
+    #[test_case("x٣y",            &['3']                ; "arabic indic between latin")]
+    #[test_case("۴ and ٤",        &['4', '4']           ; "extended arabic indic")]
+    #[test_case("３ｎｉｎｅ7",    &['3', '7']           ; "full width")]
+    #[test_case("वर्ष २०२३",       &['2', '0', '2', '3'] ; "devanagari")]
+    #[test_case("çafé five ünï9", &['5', '9']           ; "latin with diacritics")]
+    #[test_case("日本one語",      &['1']                ; "cjk")]
+    fn test_scan_numerals(line: &str, expected: &[char]) {
+        let vocabulary = Vocabulary::builder()
+            .ascii_digits()
+            .english()
+            .numerals(NumeralSystem::FullWidth)
+            .numerals(NumeralSystem::ArabicIndic)
+            .numerals(NumeralSystem::ExtendedArabicIndic)
+            .numerals(NumeralSystem::Devanagari)
+            .build()
+            .unwrap();
+        let result = vocabulary.scan(line).collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_scan_ignores_numerals_by_default() {
+        let result = WORDS_VOCABULARY.scan("٣ｏｎｅone१").collect::<Vec<_>>();
+        assert_eq!(result, ['1']);
+    }
+
+    #[test]
+    fn test_builder_deduplicates_patterns() {
+        let vocabulary = Vocabulary::builder().english().english().build().unwrap();
+        let result = vocabulary.scan("one").collect::<Vec<_>>();
+        assert_eq!(result, ['1']);
+    }
+
+    #[test_case(Language::German,  "achtzweiundneunzig", &['8', '2', '9'])]
+    #[test_case(Language::German,  "fünfsechs",          &['5', '6'])]
+    #[test_case(Language::French,  "zérodeuxtrois",      &['0', '2', '3'])]
+    #[test_case(Language::Spanish, "cuatrocientos",      &['4'])]
+    #[test_case(Language::Spanish, "docedosnueve",       &['2', '9'])]
+    fn test_scan_language(language: Language, line: &str, expected: &[char]) {
+        let vocabulary = Vocabulary::builder().language(language).build().unwrap();
+        let result = vocabulary.scan(line).collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[test_case("FÜNF und Drei",  &['5', '3']      ; "upper case umlaut")]
+    #[test_case("fÜnfSECHS",      &['5', '6']      ; "mixed case")]
+    #[test_case("Zéro ZÉRO zéro", &['0', '0', '0'] ; "french accents")]
+    #[test_case("EinsOne",        &['1', '1']      ; "german and english")]
+    fn test_scan_case_insensitive(line: &str, expected: &[char]) {
+        let vocabulary = Vocabulary::builder()
+            .language(Language::German)
+            .pattern("ZÉRO", '0')
+            .pattern("one", '1')
+            .case_insensitive()
+            .build()
+            .unwrap();
+        let result = vocabulary.scan(line).collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_scan_case_sensitive_by_default() {
+        let vocabulary = Vocabulary::builder()
+            .language(Language::German)
+            .build()
+            .unwrap();
+        let result = vocabulary.scan("FÜNF fünf Fünf").collect::<Vec<_>>();
+        assert_eq!(result, ['5']);
+    }
+
+    #[test]
+    fn test_custom_vocabulary_rejects_non_digits() {
+        assert!(Vocabulary::new([("ten", 'X')]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use num_bigint::BigUint;
+
+use crate::scanner::Token;
+
+/// Turns the tokens found on one calibration line into a value, and folds line values into a
+/// document total.
+pub trait Strategy {
+    type Output;
+    type Total: Default;
+
+    /// Returns `None` when the tokens hold no value, usually because the line had no digits.
+    fn extract<I>(&self, tokens: I) -> Option<Self::Output>
+    where
+        I: Iterator<Item = Token>;
+
+    fn accumulate(&self, total: &mut Self::Total, output: Self::Output);
+}
+
+/// The puzzle's rule: the first digit of the first token and the last digit of the last token.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstLast;
+
+impl FirstLast {
+    /// The two digits the rule picks, or `None` if there are no tokens.
+    pub fn digits<I>(tokens: I) -> Option<(u8, u8)>
+    where
+        I: Iterator<Item = Token>,
+    {
+        let mut first: Option<Token> = None;
+        let mut last: Option<Token> = None;
+
+        for token in tokens {
+            if first.is_none() {
+                first = Some(token.clone());
+            }
+            last = Some(token);
+        }
+
+        Some((first?.first_digit(), last?.last_digit()))
+    }
+
+    pub fn value((first, last): (u8, u8)) -> u8 {
+        first * 10 + last
+    }
+}
+
+impl Strategy for FirstLast {
+    type Output = u8;
+    type Total = u64;
+
+    fn extract<I>(&self, tokens: I) -> Option<Self::Output>
+    where
+        I: Iterator<Item = Token>,
+    {
+        Self::digits(tokens).map(Self::value)
+    }
+
+    fn accumulate(&self, total: &mut Self::Total, output: Self::Output) {
+        *total += output as u64;
+    }
+}
+
+/// Every digit on the line in order, with compound tokens spelled out digit by digit.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllDigits;
+
+impl Strategy for AllDigits {
+    type Output = Vec<u8>;
+    type Total = Vec<Vec<u8>>;
+
+    fn extract<I>(&self, tokens: I) -> Option<Self::Output>
+    where
+        I: Iterator<Item = Token>,
+    {
+        let digits = tokens.flat_map(|token| token.digits()).collect::<Vec<_>>();
+        (!digits.is_empty()).then_some(digits)
+    }
+
+    fn accumulate(&self, total: &mut Self::Total, output: Self::Output) {
+        total.push(output);
+    }
+}
+
+/// The matched tokens themselves, with their byte spans.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokenList;
+
+impl Strategy for TokenList {
+    type Output = Vec<Token>;
+    type Total = Vec<Vec<Token>>;
+
+    fn extract<I>(&self, tokens: I) -> Option<Self::Output>
+    where
+        I: Iterator<Item = Token>,
+    {
+        let tokens = tokens.collect::<Vec<_>>();
+        (!tokens.is_empty()).then_some(tokens)
+    }
+
+    fn accumulate(&self, total: &mut Self::Total, output: Self::Output) {
+        total.push(output);
+    }
+}
+
+/// The largest two-digit number formed by a digit and any digit after it. A line with a single
+/// digit reads that digit twice, as the puzzle's rule does.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MaxPair;
+
+impl Strategy for MaxPair {
+    type Output = u8;
+    type Total = u64;
+
+    fn extract<I>(&self, tokens: I) -> Option<Self::Output>
+    where
+        I: Iterator<Item = Token>,
+    {
+        let mut digits = tokens.flat_map(|token| token.digits());
+        let first = digits.next()?;
+
+        let mut best_tens = first;
+        let mut best: Option<u8> = None;
+        for digit in digits {
+            let pair = best_tens * 10 + digit;
+            best = Some(best.map_or(pair, |best| best.max(pair)));
+            best_tens = best_tens.max(digit);
+        }
+
+        Some(best.unwrap_or(first * 11))
+    }
+
+    fn accumulate(&self, total: &mut Self::Total, output: Self::Output) {
+        *total += output as u64;
+    }
+}
+
+/// Every digit on the line concatenated into one arbitrarily large number.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Concatenation;
+
+impl Strategy for Concatenation {
+    type Output = BigUint;
+    type Total = BigUint;
+
+    fn extract<I>(&self, tokens: I) -> Option<Self::Output>
+    where
+        I: Iterator<Item = Token>,
+    {
+        let digits = tokens.flat_map(|token| token.digits()).collect::<Vec<_>>();
+        if digits.is_empty() {
+            return None;
+        }
+        BigUint::from_radix_be(&digits, 10)
+    }
+
+    fn accumulate(&self, total: &mut Self::Total, output: Self::Output) {
+        *total += output;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::MatchMode;
+    use test_case::test_case;
+
+    #[test_case("two1nine",     &[2, 1, 9])]
+    #[test_case("twentyone7",   &[2, 1, 7])]
+    #[test_case("eightwothree", &[8, 2, 3])]
+    #[test_case("abc",          &[])]
+    fn test_all_digits(line: &str, expected: &[u8]) {
+        let result = AllDigits.extract(MatchMode::Compound.tokens(line));
+        assert_eq!(result.unwrap_or_default(), expected);
+    }
+
+    #[test]
+    fn test_token_list() {
+        let result = TokenList.extract(MatchMode::Words.tokens("a1two")).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                Token {
+                    span: 1..2,
+                    value: 1,
+                },
+                Token {
+                    span: 2..5,
+                    value: 2,
+                },
+            ]
+        );
+    }
+
+    #[test_case("1abc2", 12)]
+    #[test_case("9a1b8", 98)]
+    #[test_case("1928", 98)]
+    #[test_case("54321", 54)]
+    #[test_case("treb7uchet", 77)]
+    fn test_max_pair(line: &str, expected: u8) {
+        let result = MaxPair.extract(MatchMode::Digits.tokens(line));
+        assert_eq!(result, Some(expected));
+    }
+
+    #[test]
+    fn test_concatenation() {
+        let line = "1two3four5six7eight90one2three4five6seven8nine0";
+        let result = Concatenation
+            .extract(MatchMode::Words.tokens(line))
+            .unwrap();
+        assert_eq!(result.to_string(), "12345678901234567890");
+    }
+
+    #[test]
+    fn test_strategies_reject_empty_lines() {
+        assert_eq!(FirstLast.extract(MatchMode::Words.tokens("abc")), None);
+        assert_eq!(MaxPair.extract(MatchMode::Words.tokens("abc")), None);
+        assert_eq!(Concatenation.extract(MatchMode::Words.tokens("abc")), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Differential tests of the day-1 scanner against a brute-force reference that tries every
+//! digit word at every offset of the line.
+
+use day_1::{parse_u8, MatchMode, Vocabulary};
+use proptest::prelude::*;
+use test_case::test_case;
+
+const WORDS: [(&str, u32); 20] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Custom patterns that sit inside one another, which English digit words never do.
+const NESTED: [(&str, u32); 5] = [("abcd", 4), ("bc", 2), ("ab", 1), ("cd", 3), ("b", 5)];
+
+/// Every `(start, value)` whose word is a prefix of the suffix starting at `start`, shorter words
+/// first where several start together.
+fn reference_tokens(line: &str, words: &[(&str, u32)]) -> Vec<(usize, u32)> {
+    let mut tokens = Vec::new();
+    for (start, _) in line.char_indices() {
+        for &(word, value) in words {
+            if line[start..].starts_with(word) {
+                tokens.push((start, word.len(), value));
+            }
+        }
+    }
+    tokens.sort_unstable();
+    tokens
+        .into_iter()
+        .map(|(start, _, value)| (start, value))
+        .collect()
+}
+
+fn reference_u8(line: &str, words: &[(&str, u32)]) -> Option<u8> {
+    let tokens = reference_tokens(line, words);
+    let (_, first) = tokens.first()?;
+    let (_, last) = tokens.last()?;
+    Some((first * 10 + last) as u8)
+}
+
+fn scanner_tokens(line: &str, mode: &MatchMode) -> Vec<(usize, u32)> {
+    mode.tokens(line)
+        .map(|token| (token.span.start, token.value))
+        .collect()
+}
+
+fn nested_mode() -> MatchMode {
+    let patterns = NESTED.map(|(word, value)| (word, char::from_digit(value, 10).unwrap()));
+    MatchMode::Custom(Vocabulary::new(patterns).unwrap())
+}
+
+/// Lines built from digit words, digits, word fragments that almost match, and noise.
+fn line() -> impl Strategy<Value = String> {
+    let piece = prop_oneof![
+        prop::sample::select(WORDS.map(|(word, _)| word).to_vec()).prop_map(str::to_string),
+        prop::sample::select(vec!["zer", "on", "tw", "thre", "eigh", "nin", "fiv", "sev"])
+            .prop_map(str::to_string),
+        "[a-z]{1,3}",
+        "[é€ x-]",
+    ];
+    prop::collection::vec(piece, 0..16).prop_map(|pieces| pieces.concat())
+}
+
+/// Lines built from the nested patterns and letters they share.
+fn nested_line() -> impl Strategy<Value = String> {
+    let piece = prop_oneof![
+        prop::sample::select(NESTED.map(|(word, _)| word).to_vec()).prop_map(str::to_string),
+        "[a-e]{1,3}",
+    ];
+    prop::collection::vec(piece, 0..12).prop_map(|pieces| pieces.concat())
+}
+
+#[test_case("oneight", 18)]
+#[test_case("twone", 21)]
+#[test_case("eightwo", 82)]
+#[test_case("sevenine", 79)]
+#[test_case("threeightwo", 32)]
+#[test_case("nineight", 98)]
+fn test_overlaps(line: &str, expected: u8) {
+    assert_eq!(parse_u8(line, &MatchMode::Words), Some(expected));
+    assert_eq!(reference_u8(line, &WORDS), Some(expected));
+}
+
+#[test_case("abcd", 13)]
+#[test_case("xbcdx", 53)]
+#[test_case("abcb", 15)]
+fn test_nested(line: &str, expected: u8) {
+    assert_eq!(parse_u8(line, &nested_mode()), Some(expected));
+    assert_eq!(reference_u8(line, &NESTED), Some(expected));
+}
+
+proptest! {
+    #[test]
+    fn words_match_reference(line in line()) {
+        prop_assert_eq!(parse_u8(&line, &MatchMode::Words), reference_u8(&line, &WORDS));
+        prop_assert_eq!(scanner_tokens(&line, &MatchMode::Words), reference_tokens(&line, &WORDS));
+    }
+
+    #[test]
+    fn digits_match_reference(line in line()) {
+        let digits = &WORDS[..10];
+        prop_assert_eq!(parse_u8(&line, &MatchMode::Digits), reference_u8(&line, digits));
+        prop_assert_eq!(scanner_tokens(&line, &MatchMode::Digits), reference_tokens(&line, digits));
+    }
+
+    #[test]
+    fn nested_patterns_match_reference(line in nested_line()) {
+        let mode = nested_mode();
+        prop_assert_eq!(parse_u8(&line, &mode), reference_u8(&line, &NESTED));
+        prop_assert_eq!(scanner_tokens(&line, &mode), reference_tokens(&line, &NESTED));
+    }
+}
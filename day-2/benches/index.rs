@@ -0,0 +1,145 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day_2::batch::BagCount;
+use day_2::game::GameAnalysis;
+use day_2::index::IndexedGames;
+
+/// The `HashSet` index that the bitmaps replaced, kept here as a baseline.
+mod hash_set_index {
+    use std::collections::{BTreeMap, HashSet};
+
+    use day_2::color::Color;
+    use day_2::game::GameAnalysis;
+
+    #[derive(Debug, Default)]
+    struct ColorIndex {
+        inner: BTreeMap<u64, Vec<u64>>,
+    }
+
+    impl ColorIndex {
+        fn insert(&mut self, key: u64, value: u64) {
+            self.inner.entry(key).or_default().push(value);
+        }
+
+        fn query(&self, query: u64) -> HashSet<u64> {
+            self.inner
+                .range(..=query)
+                .flat_map(|(_, v)| v.iter().copied())
+                .collect::<HashSet<_>>()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    pub struct IndexedGames {
+        colors: BTreeMap<Color, ColorIndex>,
+    }
+
+    impl IndexedGames {
+        pub fn insert(&mut self, id: u64, analysis: &GameAnalysis) {
+            for (color, max) in analysis.maxima() {
+                self.colors
+                    .entry(color.clone())
+                    .or_default()
+                    .insert(max, id);
+            }
+        }
+
+        pub fn query(&self, query: &GameAnalysis) -> Vec<u64> {
+            let mut results: Option<HashSet<u64>> = None;
+            for (color, index) in &self.colors {
+                let color_results = index.query(query.max(color.name()));
+                results = Some(match results {
+                    Some(results) => results
+                        .intersection(&color_results)
+                        .copied()
+                        .collect::<HashSet<_>>(),
+                    None => color_results,
+                });
+            }
+            results.unwrap_or_default().into_iter().collect::<Vec<_>>()
+        }
+    }
+}
+
+/// Deterministic analyses with maxima up to 20 per color, like the puzzle input's.
+fn synthetic_games(count: u64) -> Vec<(u64, GameAnalysis)> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % 21
+    };
+    (1..=count)
+        .map(|id| (id, GameAnalysis::new(next(), next(), next())))
+        .collect()
+}
+
+fn bench_index(c: &mut Criterion) {
+    let bag = GameAnalysis::new(12, 13, 14);
+    let mut group = c.benchmark_group("day-2-index");
+    group.sample_size(10);
+
+    for count in [1_000, 100_000, 1_000_000] {
+        let games = synthetic_games(count);
+
+        let mut hash_set_games = hash_set_index::IndexedGames::default();
+        for (id, analysis) in &games {
+            hash_set_games.insert(*id, analysis);
+        }
+        let bitmap_games = games.iter().cloned().collect::<IndexedGames>();
+        assert_eq!(
+            {
+                let mut ids = hash_set_games.query(&bag);
+                ids.sort_unstable();
+                ids
+            },
+            bitmap_games.query(bag.clone())
+        );
+
+        group.bench_with_input(BenchmarkId::new("hash-set", count), &count, |b, _| {
+            b.iter(|| hash_set_games.query(black_box(&bag)).len())
+        });
+        group.bench_with_input(BenchmarkId::new("roaring", count), &count, |b, _| {
+            b.iter(|| bitmap_games.query(black_box(bag.clone())).len())
+        });
+    }
+    group.finish();
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let games = synthetic_games(100_000)
+        .into_iter()
+        .collect::<IndexedGames>();
+    let mut group = c.benchmark_group("day-2-batch");
+    group.sample_size(10);
+
+    for count in [10, 100, 1_000] {
+        let bags = synthetic_games(count)
+            .into_iter()
+            .map(|(_, bag)| bag)
+            .collect::<Vec<_>>();
+        let per_query = || {
+            bags.iter()
+                .map(|bag| {
+                    let ids = games.query(bag.clone());
+                    BagCount {
+                        games: ids.len() as u64,
+                        id_sum: ids.iter().copied().map(u128::from).sum(),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(per_query(), games.query_batch(&bags));
+
+        group.bench_with_input(BenchmarkId::new("per-query", count), &count, |b, _| {
+            b.iter(|| black_box(per_query()))
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &count, |b, _| {
+            b.iter(|| games.query_batch(black_box(&bags)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(index, bench_index, bench_batch);
+criterion_main!(index);
@@ -0,0 +1,178 @@
+//! Answers for many bags at once.
+//!
+//! `IndexedGames::query_batch` sorts the bags by their first color and sweeps that color's index
+//! once, adding each game to a Fenwick tree over the remaining colors as soon as the bags reach
+//! it. Every bag is then a prefix sum over the games it dominates, rather than an intersection of
+//! bitmaps.
+
+use std::ops::AddAssign;
+
+/// How many games a bag admits, and the sum of their ids. The sum is wide enough for every `u64`
+/// id at once.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BagCount {
+    pub games: u64,
+    pub id_sum: u128,
+}
+
+impl BagCount {
+    pub(crate) fn game(id: u64) -> Self {
+        Self {
+            games: 1,
+            id_sum: id.into(),
+        }
+    }
+}
+
+impl AddAssign for BagCount {
+    fn add_assign(&mut self, other: Self) {
+        self.games += other.games;
+        self.id_sum += other.id_sum;
+    }
+}
+
+/// A dense Fenwick tree of `BagCount`s over any number of dimensions, with 1-based coordinates.
+/// With no dimensions it is a single cell.
+#[derive(Debug)]
+pub(crate) struct Fenwick {
+    sizes: Vec<usize>,
+    strides: Vec<usize>,
+    cells: Vec<BagCount>,
+}
+
+impl Fenwick {
+    /// A tree with `sizes[d]` coordinates along dimension `d`, or `None` if it would have more than
+    /// `max_cells` cells.
+    pub(crate) fn new(sizes: Vec<usize>, max_cells: usize) -> Option<Self> {
+        let mut strides = vec![0; sizes.len()];
+        let mut cells = 1_usize;
+        for (stride, &size) in strides.iter_mut().zip(&sizes).rev() {
+            *stride = cells;
+            cells = cells
+                .checked_mul(size)
+                .filter(|&cells| cells <= max_cells)?;
+        }
+        Some(Self {
+            sizes,
+            strides,
+            cells: vec![BagCount::default(); cells],
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Where `point` lies in a grid of `len` cells laid out like the tree.
+    pub(crate) fn offset(&self, point: &[usize]) -> usize {
+        point
+            .iter()
+            .zip(&self.strides)
+            .map(|(&i, stride)| (i - 1) * stride)
+            .sum()
+    }
+
+    /// Adds every cell of `grid`, which is laid out as `offset` describes, in time linear in its
+    /// size. Leaves `grid` holding partial sums.
+    pub(crate) fn add_grid(&mut self, grid: &mut [BagCount]) {
+        for (&size, &stride) in self.sizes.iter().zip(&self.strides) {
+            for offset in 0..grid.len() {
+                let i = offset / stride % size + 1;
+                let parent = i + (i & i.wrapping_neg());
+                if parent <= size {
+                    let value = grid[offset];
+                    grid[offset + (parent - i) * stride] += value;
+                }
+            }
+        }
+        for (cell, &value) in self.cells.iter_mut().zip(grid.iter()) {
+            *cell += value;
+        }
+    }
+
+    pub(crate) fn add(&mut self, point: &[usize], value: BagCount) {
+        self.add_from(0, 0, point, value);
+    }
+
+    fn add_from(&mut self, dimension: usize, offset: usize, point: &[usize], value: BagCount) {
+        if dimension == self.sizes.len() {
+            self.cells[offset] += value;
+            return;
+        }
+        let mut i = point[dimension];
+        while i <= self.sizes[dimension] {
+            let offset = offset + (i - 1) * self.strides[dimension];
+            self.add_from(dimension + 1, offset, point, value);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The sum over every cell at or below `point` in all dimensions. A zero coordinate gives an
+    /// empty sum.
+    pub(crate) fn prefix(&self, point: &[usize]) -> BagCount {
+        let mut sum = BagCount::default();
+        self.prefix_from(0, 0, point, &mut sum);
+        sum
+    }
+
+    fn prefix_from(&self, dimension: usize, offset: usize, point: &[usize], sum: &mut BagCount) {
+        if dimension == self.sizes.len() {
+            *sum += self.cells[offset];
+            return;
+        }
+        let mut i = point[dimension];
+        while i > 0 {
+            let offset = offset + (i - 1) * self.strides[dimension];
+            self.prefix_from(dimension + 1, offset, point, sum);
+            i -= i & i.wrapping_neg();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fenwick_matches_brute_force() {
+        let points = [[1, 3], [2, 2], [3, 1], [3, 3], [1, 1], [2, 3]];
+        let mut fenwick = Fenwick::new(vec![3, 3], 9).unwrap();
+        for (id, point) in points.iter().enumerate() {
+            fenwick.add(point, BagCount::game(id as u64));
+        }
+        for x in 0..=3 {
+            for y in 0..=3 {
+                let mut expected = BagCount::default();
+                for (id, point) in points.iter().enumerate() {
+                    if point[0] <= x && point[1] <= y {
+                        expected += BagCount::game(id as u64);
+                    }
+                }
+                assert_eq!(fenwick.prefix(&[x, y]), expected, "({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fenwick_add_grid() {
+        let points = [[1, 2, 1], [2, 2, 2], [1, 1, 3], [2, 1, 3], [1, 2, 1]];
+        let mut added = Fenwick::new(vec![2, 2, 3], 12).unwrap();
+        let mut gridded = Fenwick::new(vec![2, 2, 3], 12).unwrap();
+        let mut grid = vec![BagCount::default(); gridded.len()];
+        for (id, point) in points.iter().enumerate() {
+            added.add(point, BagCount::game(id as u64));
+            grid[gridded.offset(point)] += BagCount::game(id as u64);
+        }
+        gridded.add_grid(&mut grid);
+        assert_eq!(added.cells, gridded.cells);
+    }
+
+    #[test]
+    fn test_fenwick_limits_cells() {
+        assert!(Fenwick::new(vec![3, 4], 11).is_none());
+        assert!(Fenwick::new(vec![usize::MAX, 2], usize::MAX).is_none());
+        let mut point = Fenwick::new(Vec::new(), 1).unwrap();
+        point.add(&[], BagCount::game(4));
+        assert_eq!(point.prefix(&[]), BagCount::game(4));
+    }
+}
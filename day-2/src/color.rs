@@ -0,0 +1,140 @@
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// A cube color. Colors compare by name, so a `Color` made with `Color::new` equals the one
+/// interned in a `ColorSet`; interning only saves allocating the name once per game.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Color(Arc<str>);
+
+impl Color {
+    pub fn new(name: &str) -> Self {
+        Self(Arc::from(name))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Color {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The colors a game log may mention.
+///
+/// A declared set rejects any other color; a discovering set grows as the log is parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColorSet {
+    colors: BTreeSet<Color>,
+    discovering: bool,
+}
+
+impl Default for ColorSet {
+    /// The red, green and blue cubes of the puzzle.
+    fn default() -> Self {
+        Self::rgb()
+    }
+}
+
+impl ColorSet {
+    pub fn rgb() -> Self {
+        Self::declared(["red", "green", "blue"])
+    }
+
+    pub fn declared<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            colors: names
+                .into_iter()
+                .map(|name| Color::new(name.as_ref()))
+                .collect(),
+            discovering: false,
+        }
+    }
+
+    pub fn discovering() -> Self {
+        Self {
+            colors: BTreeSet::new(),
+            discovering: true,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Color> {
+        self.colors.get(name)
+    }
+
+    /// Returns the set's color called `name`, adding it first if the set is discovering. Returns
+    /// `None` for a color a declared set does not contain.
+    pub fn intern(&mut self, name: &str) -> Option<Color> {
+        if let Some(color) = self.colors.get(name) {
+            return Some(color.clone());
+        }
+        if !self.discovering {
+            return None;
+        }
+        let color = Color::new(name);
+        self.colors.insert(color.clone());
+        Some(color)
+    }
+
+    /// Takes back a color `intern` added.
+    pub(crate) fn remove(&mut self, color: &Color) {
+        self.colors.remove(color);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.colors.contains(name)
+    }
+
+    /// The colors in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &Color> {
+        self.colors.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_rejects_unknown() {
+        let mut colors = ColorSet::rgb();
+        assert_eq!(colors.intern("red"), Some(Color::new("red")));
+        assert_eq!(colors.intern("yellow"), None);
+        assert_eq!(colors.len(), 3);
+    }
+
+    #[test]
+    fn test_discovering_interns_once() {
+        let mut colors = ColorSet::discovering();
+        let first = colors.intern("yellow").unwrap();
+        let second = colors.intern("yellow").unwrap();
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        colors.intern("purple");
+        assert_eq!(
+            colors.iter().map(Color::name).collect::<Vec<_>>(),
+            ["purple", "yellow"]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use displaydoc::Display;
+
+/// What the parser expected where a game line went wrong.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// expected `Game `
+    MissingPrefix,
+    /// expected `:` after the game id
+    MissingColon,
+    /// expected a game id
+    InvalidId,
+    /// expected a cube count
+    InvalidCount,
+    /// expected a color after the count
+    MissingColor,
+    /// expected one of the declared colors
+    UnknownColor,
+    /// expected each color at most once per draw
+    DuplicateColor,
+}
+
+/// A game line that failed to parse, with the position of the offending token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// 1-based line number, when the line came from a log.
+    pub line: Option<usize>,
+    /// Byte offset of `token` within `source`.
+    pub offset: usize,
+    pub token: String,
+    /// The whole line.
+    pub source: String,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind, source: &str, offset: usize, token: &str) -> Self {
+        Self {
+            kind,
+            line: None,
+            offset,
+            token: token.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    pub(crate) fn at_line(self, line: usize) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    /// The message followed by the line and a caret under the offending token:
+    ///
+    /// ```text
+    /// line 3, byte 15: expected a cube count, found "x"
+    /// Game 3: 1 red, x green
+    ///                ^
+    /// ```
+    pub fn render(&self) -> String {
+        let column = self.source[..self.offset].chars().count();
+        let width = self.token.chars().count().max(1);
+        format!(
+            "{self}\n{}\n{}{}",
+            self.source,
+            " ".repeat(column),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}, ")?;
+        }
+        write!(f, "byte {}: {}, ", self.offset, self.kind)?;
+        if self.token.is_empty() {
+            f.write_str("found nothing")
+        } else {
+            write!(f, "found {:?}", self.token)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for aoc_common::Error {
+    fn from(error: Error) -> Self {
+        Self::InvalidInput(error.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let source = "Game 3: 1 red, x green";
+        let error = Error::new(ErrorKind::InvalidCount, source, 15, &source[15..16]).at_line(3);
+        assert_eq!(
+            error.render(),
+            "line 3, byte 15: expected a cube count, found \"x\"\n\
+             Game 3: 1 red, x green\n               \
+             ^"
+        );
+    }
+
+    #[test]
+    fn test_render_counts_columns_in_chars() {
+        let source = "Game 1: 2 grün";
+        let error = Error::new(ErrorKind::UnknownColor, source, 10, &source[10..]);
+        assert_eq!(error.render().lines().last(), Some("          ^^^^"));
+    }
+}
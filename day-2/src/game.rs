@@ -1,10 +1,99 @@
-use displaydoc::Display;
-use thiserror::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
 
-#[derive(Debug, Display, Error)]
-pub enum Error {
-    /// Invalid game string: {0}
-    InvalidGameString(String),
+use crate::color::{Color, ColorSet};
+use crate::error::{Error, ErrorKind};
+
+/// What to do when a draw lists the same color twice, as in `3 red, 2 red`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Add the counts up, treating the entries as one split handful.
+    #[default]
+    Sum,
+    /// Keep the largest count.
+    Max,
+    /// Fail with an error pointing at the repeated color.
+    Reject,
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = aoc_common::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Self::Sum),
+            "max" => Ok(Self::Max),
+            "reject" => Ok(Self::Reject),
+            _ => Err(aoc_common::Error::InvalidArgument(format!(
+                "duplicate policy {s:?}, expected sum, max or reject"
+            ))),
+        }
+    }
+}
+
+/// Every game of a log, with the colors they were parsed against.
+#[derive(Debug, Default)]
+pub struct GameLog {
+    pub colors: ColorSet,
+    pub games: Vec<Game>,
+}
+
+impl GameLog {
+    pub fn parse<R>(
+        reader: R,
+        mut colors: ColorSet,
+        duplicates: DuplicatePolicy,
+    ) -> aoc_common::Result<Self>
+    where
+        R: BufRead,
+    {
+        let games = reader
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                Game::parse_with(&line?, &mut colors, duplicates)
+                    .map_err(|error| error.at_line(index + 1).into())
+            })
+            .collect::<aoc_common::Result<_>>()?;
+        Ok(Self { colors, games })
+    }
+
+    /// Parses every line it can, returning the log of the good games and an error for each bad
+    /// line instead of stopping at the first. A discovering set only keeps the colors of the good
+    /// lines.
+    pub fn parse_all<R>(
+        reader: R,
+        mut colors: ColorSet,
+        duplicates: DuplicatePolicy,
+    ) -> aoc_common::Result<(Self, Vec<Error>)>
+    where
+        R: BufRead,
+    {
+        let mut games = Vec::new();
+        let mut errors = Vec::new();
+        let mut added = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            match Game::parse_tracked(&line?, &mut colors, duplicates, &mut added) {
+                Ok(game) => games.push(game),
+                Err(error) => {
+                    for color in &added {
+                        colors.remove(color);
+                    }
+                    errors.push(error.at_line(index + 1));
+                }
+            }
+            added.clear();
+        }
+        Ok((Self { colors, games }, errors))
+    }
+
+    pub fn analyses(&self) -> impl Iterator<Item = (u64, GameAnalysis)> + '_ {
+        self.games
+            .iter()
+            .map(|game| (game.id, game.analyze(&self.colors)))
+    }
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
@@ -13,107 +102,283 @@ pub struct Game {
     iterations: Vec<GameIteration>,
 }
 
+/// The cubes shown in one draw. Colors that were not shown, or shown zero times, are absent.
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct GameIteration {
-    red: u64,
-    blue: u64,
-    green: u64,
+    counts: BTreeMap<Color, u64>,
+}
+
+impl GameIteration {
+    pub fn count(&self, color: &str) -> u64 {
+        self.counts.get(color).copied().unwrap_or_default()
+    }
+
+    pub fn counts(&self) -> impl Iterator<Item = (&Color, u64)> {
+        self.counts.iter().map(|(color, &count)| (color, count))
+    }
+
+    /// Whether the draw shows no cubes at all.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Records `count` cubes of `color`, combining them with any already recorded as `duplicates`
+    /// says. `Reject` is enforced by the parser, which knows where the entries are; here it keeps
+    /// the first count.
+    pub fn add(&mut self, color: Color, count: u64, duplicates: DuplicatePolicy) {
+        if count == 0 {
+            return;
+        }
+        let entry = self.counts.entry(color).or_default();
+        *entry = match duplicates {
+            DuplicatePolicy::Sum => entry.saturating_add(count),
+            DuplicatePolicy::Max => (*entry).max(count),
+            DuplicatePolicy::Reject if *entry == 0 => count,
+            DuplicatePolicy::Reject => *entry,
+        };
+    }
+}
+
+/// Collects entries under the default `Sum` policy.
+impl FromIterator<(Color, u64)> for GameIteration {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (Color, u64)>,
+    {
+        let mut iteration = Self::default();
+        for (color, count) in iter {
+            iteration.add(color, count, DuplicatePolicy::default());
+        }
+        iteration
+    }
 }
 
 impl Game {
-    pub fn analyze(&self) -> GameAnalysis {
-        let mut max_red = 0;
-        let mut max_blue = 0;
-        let mut max_green = 0;
+    /// A game of `iterations`, leaving out any draw that shows no cubes.
+    pub fn new(id: u64, mut iterations: Vec<GameIteration>) -> Self {
+        iterations.retain(|iteration| !iteration.is_empty());
+        Self { id, iterations }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn iterations(&self) -> &[GameIteration] {
+        &self.iterations
+    }
+
+    /// The largest count of every color in `colors` across the game's draws, zero for colors the
+    /// game never shows.
+    pub fn analyze(&self, colors: &ColorSet) -> GameAnalysis {
+        let mut maxima = colors
+            .iter()
+            .map(|color| (color.clone(), 0))
+            .collect::<BTreeMap<_, _>>();
         for iteration in &self.iterations {
-            if iteration.red > max_red {
-                max_red = iteration.red;
-            }
-            if iteration.blue > max_blue {
-                max_blue = iteration.blue;
-            }
-            if iteration.green > max_green {
-                max_green = iteration.green;
+            for (color, count) in iteration.counts() {
+                let max = maxima.entry(color.clone()).or_default();
+                if count > *max {
+                    *max = count;
+                }
             }
         }
 
-        GameAnalysis {
-            max_red,
-            max_blue,
-            max_green,
-        }
+        GameAnalysis { maxima }
     }
-}
 
-impl TryFrom<&str> for Game {
-    type Error = Error;
+    /// Parses a `Game N: 3 blue, 4 red; ...` line, interning its colors in `colors` and summing
+    /// repeated colors. Draws that show no cubes, such as `0 red` or an empty one, are left out, so
+    /// `Game N:` is a game of no draws.
+    pub fn parse(value: &str, colors: &mut ColorSet) -> Result<Self, Error> {
+        Self::parse_with(value, colors, DuplicatePolicy::default())
+    }
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut game = Game::default();
+    pub fn parse_with(
+        value: &str,
+        colors: &mut ColorSet,
+        duplicates: DuplicatePolicy,
+    ) -> Result<Self, Error> {
+        Self::parse_tracked(value, colors, duplicates, &mut Vec::new())
+    }
 
-        let (game_prefix, game_body) = value
-            .split_once(": ")
-            .ok_or_else(|| Error::InvalidGameString(format!("invalid game string: {}", value)))?;
+    /// Like `parse_with`, but pushes every color it adds to `colors` onto `added`, so that a caller
+    /// can take them back if the line turns out to be bad.
+    fn parse_tracked(
+        value: &str,
+        colors: &mut ColorSet,
+        duplicates: DuplicatePolicy,
+        added: &mut Vec<Color>,
+    ) -> Result<Self, Error> {
+        let mut game = Game::default();
+        let error = |kind, offset, token| Error::new(kind, value, offset, token);
 
-        let id = game_prefix
+        let rest = value
             .strip_prefix("Game ")
-            .ok_or_else(|| Error::InvalidGameString(format!("invalid game string: {}", value)))?
-            .parse::<u64>()
-            .map_err(|_| Error::InvalidGameString(format!("invalid game string: {}", value)))?;
+            .ok_or_else(|| error(ErrorKind::MissingPrefix, 0, first_word(value)))?;
+        let rest_offset = value.len() - rest.len();
 
-        game.id = id;
+        let (id, game_body) = rest.split_once(':').ok_or_else(|| {
+            let after_id = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+            let offset = value.len() - after_id.len();
+            error(ErrorKind::MissingColon, offset, first_word(after_id))
+        })?;
 
-        for game_iteration_body in game_body.split("; ") {
+        let id_offset = rest_offset + (id.len() - id.trim_start().len());
+        let id = id.trim();
+        game.id = id
+            .parse::<u64>()
+            .map_err(|_| error(ErrorKind::InvalidId, id_offset, id))?;
+
+        // The body ends the line, so its offset is what precedes it.
+        let body = game_body.trim_start();
+        let body_offset = value.len() - body.len();
+        for (draw_offset, game_iteration_body) in pieces(body, body_offset, "; ") {
             let mut game_iteration = GameIteration::default();
+            let mut seen = BTreeSet::new();
+            if game_iteration_body.is_empty() {
+                continue;
+            }
 
-            for color_result in game_iteration_body.split(", ") {
-                let (color_count_str, color_body) =
-                    color_result.split_once(" ").ok_or_else(|| {
-                        Error::InvalidGameString(format!("invalid game string: {}", value))
-                    })?;
-
-                let color_count = color_count_str.parse::<u64>().map_err(|_| {
-                    Error::InvalidGameString(format!("invalid game string: {}", value))
-                })?;
-
-                match color_body {
-                    "red" => game_iteration.red = color_count,
-                    "blue" => game_iteration.blue = color_count,
-                    "green" => game_iteration.green = color_count,
-                    _ => {
-                        return Err(Error::InvalidGameString(format!(
-                            "invalid game string: {}",
-                            value
-                        )))
-                    }
+            for (cube_offset, color_result) in pieces(game_iteration_body, draw_offset, ", ") {
+                let (color_count_str, color_body) = match color_result.split_once(' ') {
+                    Some(parts) => parts,
+                    None => (color_result, ""),
+                };
+                let color_offset = cube_offset + color_result.len() - color_body.len();
+
+                let color_count = color_count_str
+                    .parse::<u64>()
+                    .map_err(|_| error(ErrorKind::InvalidCount, cube_offset, color_count_str))?;
+
+                if color_body.is_empty() {
+                    return Err(error(ErrorKind::MissingColor, color_offset, color_body));
+                }
+                let known = colors.contains(color_body);
+                let color = colors
+                    .intern(color_body)
+                    .ok_or_else(|| error(ErrorKind::UnknownColor, color_offset, color_body))?;
+                if !known {
+                    added.push(color.clone());
                 }
+                if duplicates == DuplicatePolicy::Reject && !seen.insert(color.clone()) {
+                    return Err(error(ErrorKind::DuplicateColor, color_offset, color_body));
+                }
+                game_iteration.add(color, color_count, duplicates);
             }
 
-            game.iterations.push(game_iteration);
+            if !game_iteration.is_empty() {
+                game.iterations.push(game_iteration);
+            }
         }
 
         Ok(game)
     }
 }
 
-#[derive(Debug, Default, Eq, PartialEq)]
+/// The pieces of `text` between `separator`s, each with its byte offset in the line, given that
+/// `text` starts at `offset`.
+fn pieces<'a>(
+    text: &'a str,
+    offset: usize,
+    separator: &'a str,
+) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+    text.split(separator).scan(offset, move |start, piece| {
+        let piece_offset = *start;
+        *start += piece.len() + separator.len();
+        Some((piece_offset, piece))
+    })
+}
+
+/// The leading run of non-space characters of `value`, as a slice of it.
+fn first_word(value: &str) -> &str {
+    let end = value.find(' ').unwrap_or(value.len());
+    &value[..end]
+}
+
+/// Writes the canonical form of the line: colors in name order, with zero counts and draws that
+/// show no cubes left out, so that `Game::parse` reads back an equal game.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        for (index, iteration) in self.iterations.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{iteration}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GameIteration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (color, count)) in self.counts().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{count} {color}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes one canonical game per line.
+impl fmt::Display for GameLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for game in &self.games {
+            writeln!(f, "{game}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Game {
+    type Error = Error;
+
+    /// Parses a line of the puzzle's red, green and blue cubes.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value, &mut ColorSet::rgb())
+    }
+}
+
+/// The largest count of each color shown in a game, which is also the smallest bag the game could
+/// have been played with.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct GameAnalysis {
-    pub max_red: u64,
-    pub max_blue: u64,
-    pub max_green: u64,
+    maxima: BTreeMap<Color, u64>,
 }
 
 impl GameAnalysis {
     pub fn new(red: u64, green: u64, blue: u64) -> Self {
-        Self {
-            max_red: red,
-            max_blue: blue,
-            max_green: green,
-        }
+        [("red", red), ("green", green), ("blue", blue)]
+            .into_iter()
+            .map(|(name, max)| (Color::new(name), max))
+            .collect()
     }
 
+    pub fn max(&self, color: &str) -> u64 {
+        self.maxima.get(color).copied().unwrap_or_default()
+    }
+
+    pub fn maxima(&self) -> impl Iterator<Item = (&Color, u64)> {
+        self.maxima.iter().map(|(color, &max)| (color, max))
+    }
+
+    /// The power of the smallest bag: the product of the maxima of every color.
     pub fn min_cube(&self) -> u64 {
-        self.max_red * self.max_green * self.max_blue
+        self.maxima.values().product()
+    }
+}
+
+impl FromIterator<(Color, u64)> for GameAnalysis {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (Color, u64)>,
+    {
+        Self {
+            maxima: iter.into_iter().collect(),
+        }
     }
 }
 
@@ -122,26 +387,21 @@ mod test {
     use super::*;
     use test_case::test_case;
 
+    fn rgb(red: u64, blue: u64, green: u64) -> GameIteration {
+        [("red", red), ("blue", blue), ("green", green)]
+            .into_iter()
+            .map(|(name, count)| (Color::new(name), count))
+            .collect()
+    }
+
     #[test_case(
         r#"Game 1: 1 red, 1 blue, 1 green; 2 red, 2 blue, 2 green; 3 red, 3 blue, 3 green"#,
         Game {
             id: 1,
             iterations: vec![
-                GameIteration {
-                    red: 1,
-                    blue: 1,
-                    green: 1,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 2,
-                    green: 2,
-                },
-                GameIteration {
-                    red: 3,
-                    blue: 3,
-                    green: 3,
-                },
+                rgb(1, 1, 1),
+                rgb(2, 2, 2),
+                rgb(3, 3, 3),
             ],
         }
         ; "game 1"
@@ -151,21 +411,9 @@ mod test {
         Game {
             id: 2,
             iterations: vec![
-                GameIteration {
-                    red: 4,
-                    blue: 4,
-                    green: 3,
-                },
-                GameIteration {
-                    red: 6,
-                    blue: 4,
-                    green: 4,
-                },
-                GameIteration {
-                    red: 3,
-                    blue: 2,
-                    green: 4,
-                },
+                rgb(4, 4, 3),
+                rgb(6, 4, 4),
+                rgb(3, 2, 4),
             ],
         }
         ; "game 2"
@@ -175,26 +423,10 @@ mod test {
         Game {
             id: 3,
             iterations: vec![
-                GameIteration {
-                    red: 1,
-                    blue: 3,
-                    green: 2,
-                },
-                GameIteration {
-                    red: 1,
-                    blue: 0,
-                    green: 2,
-                },
-                GameIteration {
-                    red: 3,
-                    blue: 0,
-                    green: 2,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 1,
-                    green: 0,
-                },
+                rgb(1, 3, 2),
+                rgb(1, 0, 2),
+                rgb(3, 0, 2),
+                rgb(2, 1, 0),
             ],
         }
         ; "game 3"
@@ -204,31 +436,11 @@ mod test {
         Game {
             id: 4,
             iterations: vec![
-                GameIteration {
-                    red: 1,
-                    blue: 0,
-                    green: 15,
-                },
-                GameIteration {
-                    red: 0,
-                    blue: 2,
-                    green: 1,
-                },
-                GameIteration {
-                    red: 1,
-                    blue: 2,
-                    green: 12,
-                },
-                GameIteration {
-                    red: 0,
-                    blue: 0,
-                    green: 14,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 1,
-                    green: 2,
-                },
+                rgb(1, 0, 15),
+                rgb(0, 2, 1),
+                rgb(1, 2, 12),
+                rgb(0, 0, 14),
+                rgb(2, 1, 2),
             ],
         }
         ; "game 4"
@@ -238,36 +450,12 @@ mod test {
         Game {
             id: 5,
             iterations: vec![
-                GameIteration {
-                    red: 8,
-                    blue: 0,
-                    green: 0,
-                },
-                GameIteration {
-                    red: 7,
-                    blue: 0,
-                    green: 0,
-                },
-                GameIteration {
-                    red: 11,
-                    blue: 0,
-                    green: 4,
-                },
-                GameIteration {
-                    red: 8,
-                    blue: 1,
-                    green: 0,
-                },
-                GameIteration {
-                    red: 6,
-                    blue: 1,
-                    green: 2,
-                },
-                GameIteration {
-                    red: 13,
-                    blue: 1,
-                    green: 8,
-                },
+                rgb(8, 0, 0),
+                rgb(7, 0, 0),
+                rgb(11, 0, 4),
+                rgb(8, 1, 0),
+                rgb(6, 1, 2),
+                rgb(13, 1, 8),
             ],
         }
         ; "game 5"
@@ -277,21 +465,9 @@ mod test {
         Game {
             id: 6,
             iterations: vec![
-                GameIteration {
-                    red: 2,
-                    blue: 3,
-                    green: 6,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 1,
-                    green: 8,
-                },
-                GameIteration {
-                    red: 1,
-                    blue: 3,
-                    green: 0,
-                },
+                rgb(2, 3, 6),
+                rgb(2, 1, 8),
+                rgb(1, 3, 0),
             ],
         }
         ; "game 6"
@@ -301,26 +477,10 @@ mod test {
         Game {
             id: 7,
             iterations: vec![
-                GameIteration {
-                    red: 1,
-                    blue: 1,
-                    green: 5,
-                },
-                GameIteration {
-                    red: 12,
-                    blue: 6,
-                    green: 0,
-                },
-                GameIteration {
-                    red: 6,
-                    blue: 0,
-                    green: 7,
-                },
-                GameIteration {
-                    red: 0,
-                    blue: 1,
-                    green: 3,
-                },
+                rgb(1, 1, 5),
+                rgb(12, 6, 0),
+                rgb(6, 0, 7),
+                rgb(0, 1, 3),
             ],
         }
         ; "game 7"
@@ -330,26 +490,10 @@ mod test {
         Game {
             id: 8,
             iterations: vec![
-                GameIteration {
-                    red: 10,
-                    blue: 0,
-                    green: 6,
-                },
-                GameIteration {
-                    red: 5,
-                    blue: 4,
-                    green: 6,
-                },
-                GameIteration {
-                    red: 5,
-                    blue: 5,
-                    green: 8,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 4,
-                    green: 0,
-                },
+                rgb(10, 0, 6),
+                rgb(5, 4, 6),
+                rgb(5, 5, 8),
+                rgb(2, 4, 0),
             ],
         }
         ; "game 8"
@@ -359,36 +503,12 @@ mod test {
         Game {
             id: 9,
             iterations: vec![
-                GameIteration {
-                    red: 13,
-                    blue: 11,
-                    green: 3,
-                },
-                GameIteration {
-                    red: 13,
-                    blue: 6,
-                    green: 1,
-                },
-                GameIteration {
-                    red: 5,
-                    blue: 8,
-                    green: 4,
-                },
-                GameIteration {
-                    red: 16,
-                    blue: 10,
-                    green: 7,
-                },
-                GameIteration {
-                    red: 16,
-                    blue: 6,
-                    green: 5,
-                },
-                GameIteration {
-                    red: 17,
-                    blue: 6,
-                    green: 0,
-                },
+                rgb(13, 11, 3),
+                rgb(13, 6, 1),
+                rgb(5, 8, 4),
+                rgb(16, 10, 7),
+                rgb(16, 6, 5),
+                rgb(17, 6, 0),
             ],
         }
         ; "game 9"
@@ -398,26 +518,10 @@ mod test {
         Game {
             id: 10,
             iterations: vec![
-                GameIteration {
-                    red: 0,
-                    blue: 16,
-                    green: 8,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 1,
-                    green: 4,
-                },
-                GameIteration {
-                    red: 0,
-                    blue: 15,
-                    green: 0,
-                },
-                GameIteration {
-                    red: 4,
-                    blue: 4,
-                    green: 5,
-                },
+                rgb(0, 16, 8),
+                rgb(2, 1, 4),
+                rgb(0, 15, 0),
+                rgb(4, 4, 5),
             ],
         }
         ; "game 10"
@@ -431,34 +535,142 @@ mod test {
     fn test_game_analysis_from_game() {
         let game = Game {
             id: 1,
-            iterations: vec![
-                GameIteration {
-                    red: 10,
-                    blue: 1,
-                    green: 1,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 0,
-                    green: 22,
-                },
-                GameIteration {
-                    red: 2,
-                    blue: 0,
-                    green: 0,
-                },
-            ],
+            iterations: vec![rgb(10, 1, 1), rgb(2, 0, 22), rgb(2, 0, 0)],
         };
 
-        let result = game.analyze();
+        let result = game.analyze(&ColorSet::rgb());
+
+        assert_eq!(result, GameAnalysis::new(10, 22, 1));
+    }
+
+    #[test]
+    fn test_game_analysis_counts_unseen_colors_as_zero() {
+        let game = Game::try_from("Game 1: 3 red, 2 green; 1 red").unwrap();
+        let result = game.analyze(&ColorSet::rgb());
+        assert_eq!(result, GameAnalysis::new(3, 2, 0));
+        assert_eq!(result.min_cube(), 0);
+    }
 
+    #[test]
+    fn test_game_arbitrary_colors() {
+        let mut colors = ColorSet::discovering();
+        let game = Game::parse("Game 7: 3 yellow, 1 red; 2 purple, 4 yellow", &mut colors).unwrap();
+        let result = game.analyze(&colors);
         assert_eq!(
-            result,
-            GameAnalysis {
-                max_red: 10,
-                max_blue: 1,
-                max_green: 22,
-            }
+            result.maxima().collect::<Vec<_>>(),
+            [
+                (&Color::new("purple"), 2),
+                (&Color::new("red"), 1),
+                (&Color::new("yellow"), 4),
+            ]
+        );
+        assert_eq!(result.min_cube(), 8);
+    }
+
+    #[test]
+    fn test_game_rejects_undeclared_color() {
+        let mut colors = ColorSet::declared(["red", "yellow"]);
+        let result = Game::parse("Game 1: 3 yellow, 1 blue", &mut colors);
+        assert!(
+            matches!(result, Err(Error { kind: ErrorKind::UnknownColor, token, .. }) if token == "blue")
+        );
+    }
+
+    #[test_case(
+        "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue",
+        "Game 1: 3 blue, 4 red; 6 blue, 2 green, 1 red"
+    )]
+    #[test_case("Game 2: 0 red, 2 green; 0 blue", "Game 2: 2 green")]
+    #[test_case("Game 3: 1 red, 4 red",           "Game 3: 5 red")]
+    #[test_case("Game 4: ; 0 red",                "Game 4: ")]
+    #[test_case("Game 5:",                        "Game 5: ")]
+    #[test_case("Game 6 :1 red",                  "Game 6: 1 red")]
+    fn test_game_display(input: &str, expected: &str) {
+        let game = Game::try_from(input).unwrap();
+        assert_eq!(game.to_string(), expected);
+        assert_eq!(Game::try_from(expected).unwrap(), game);
+    }
+
+    #[test_case(DuplicatePolicy::Sum, GameAnalysis::new(5, 2, 1))]
+    #[test_case(DuplicatePolicy::Max, GameAnalysis::new(3, 2, 1))]
+    fn test_game_duplicate_colors(duplicates: DuplicatePolicy, expected: GameAnalysis) {
+        let line = "Game 1: 3 red, 1 blue, 2 red; 2 green, 0 green";
+        let game = Game::parse_with(line, &mut ColorSet::rgb(), duplicates).unwrap();
+        assert_eq!(game.analyze(&ColorSet::rgb()), expected);
+    }
+
+    #[test]
+    fn test_game_rejects_duplicate_colors() {
+        let line = "Game 1: 3 red, 1 blue; 2 green, 0 green";
+        let result = Game::parse_with(line, &mut ColorSet::rgb(), DuplicatePolicy::Reject);
+        let error = result.unwrap_err();
+        assert_eq!(
+            (error.kind, error.offset, error.token.as_str()),
+            (ErrorKind::DuplicateColor, 34, "green")
+        );
+    }
+
+    #[test_case("Gme 1: 1 red",          ErrorKind::MissingPrefix, 0,  "Gme")]
+    #[test_case("Game 1 1 red",          ErrorKind::MissingColon,  6,  "")]
+    #[test_case("Game 1; 1 red",         ErrorKind::MissingColon,  6,  ";")]
+    #[test_case("Game x: 1 red",         ErrorKind::InvalidId,     5,  "x")]
+    #[test_case("Game : 1 red",          ErrorKind::InvalidId,     5,  "")]
+    #[test_case("Game 2: 1 red, x blue", ErrorKind::InvalidCount,  15, "x")]
+    #[test_case("Game 2: 1 red; 3",      ErrorKind::MissingColor,  16, "")]
+    #[test_case("Game 2: 1 red, 4 teal", ErrorKind::UnknownColor,  17, "teal")]
+    #[test_case("Game 2: 1 red,2 blue",  ErrorKind::UnknownColor,  10, "red,2 blue")]
+    fn test_game_parse_errors(line: &str, kind: ErrorKind, offset: usize, token: &str) {
+        let result = Game::try_from(line).unwrap_err();
+        assert_eq!(
+            (result.kind, result.offset, result.token.as_str()),
+            (kind, offset, token)
+        );
+    }
+
+    #[test]
+    fn test_game_log_parse_all() {
+        let input = "Game 1: 1 red\nGame 2: 1 rde\nGame 3: 2 blue\nGame: 1 red\n";
+        let (log, errors) =
+            GameLog::parse_all(input.as_bytes(), ColorSet::rgb(), DuplicatePolicy::Sum).unwrap();
+        assert_eq!(log.games.iter().map(Game::id).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.line, error.kind))
+                .collect::<Vec<_>>(),
+            [
+                (Some(2), ErrorKind::UnknownColor),
+                (Some(4), ErrorKind::MissingPrefix),
+            ]
+        );
+
+        let input = "Game 1: 1 red, 1 green, 1 blue\nGame 2: 2 teal, x red\n";
+        let (log, errors) = GameLog::parse_all(
+            input.as_bytes(),
+            ColorSet::discovering(),
+            DuplicatePolicy::Sum,
+        )
+        .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(!log.colors.contains("teal"));
+        assert_eq!(
+            log.analyses()
+                .map(|(_, analysis)| analysis.min_cube())
+                .collect::<Vec<_>>(),
+            [1]
+        );
+
+        let input = "Game 1: 1 cyan\nGame 2: 1 teal, 2 cyan, 3 red, x blue\nGame 3: 1 red\n";
+        let (log, errors) = GameLog::parse_all(
+            input.as_bytes(),
+            ColorSet::discovering(),
+            DuplicatePolicy::Sum,
+        )
+        .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            log.colors.iter().map(Color::name).collect::<Vec<_>>(),
+            ["cyan", "red"]
         );
     }
 }
@@ -1,52 +1,731 @@
+use crate::batch::{BagCount, Fenwick};
+use crate::color::Color;
 use crate::game::GameAnalysis;
-use std::collections::{BTreeMap, HashSet};
+use crate::query::{self, CompareOp, Field, Query};
+use crate::snapshot;
+use aoc_common::Result;
+use roaring::{MultiOps, RoaringTreemap};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::{Bound, RangeInclusive};
+use std::path::Path;
+use tempfile::NamedTempFile;
 
+/// The most Fenwick tree cells `IndexedGames::query_batch` allocates before it falls back to
+/// answering bags one at a time.
+const MAX_BATCH_CELLS: usize = 1 << 22;
+
+/// Which end of a ranking comes first. Games that tie keep ascending id order either way.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// The ids of the games with each maximum of one color, as compressed bitmaps.
 #[derive(Debug, Default)]
 struct ColorIndex {
-    inner: BTreeMap<u64, Vec<u64>>,
+    inner: BTreeMap<u64, RoaringTreemap>,
 }
 
 impl ColorIndex {
     fn insert(&mut self, key: u64, value: u64) {
-        self.inner.entry(key).or_insert_with(Vec::new).push(value);
+        self.inner.entry(key).or_default().insert(value);
+    }
+
+    fn remove(&mut self, key: u64, value: u64) {
+        if let Some(ids) = self.inner.get_mut(&key) {
+            ids.remove(value);
+            if ids.is_empty() {
+                self.inner.remove(&key);
+            }
+        }
+    }
+
+    fn query(&self, query: u64) -> RoaringTreemap {
+        self.range(0..=query)
     }
 
-    fn query(&self, query: u64) -> HashSet<u64> {
-        self.inner
-            .range(..=query)
-            .map(|(_, v)| v.iter().copied())
-            .flatten()
-            .collect::<HashSet<_>>()
+    fn range(&self, range: RangeInclusive<u64>) -> RoaringTreemap {
+        self.inner.range(range).map(|(_, ids)| ids).union()
     }
 }
 
+/// Game ids indexed by their maximum of every color seen so far. A game indexed before a color
+/// first appears counts as having shown none of it.
 #[derive(Debug, Default)]
 pub struct IndexedGames {
-    red: ColorIndex,
-    green: ColorIndex,
-    blue: ColorIndex,
+    colors: BTreeMap<Color, ColorIndex>,
+    games: BTreeMap<u64, GameAnalysis>,
+    ids: RoaringTreemap,
 }
 
 impl IndexedGames {
-    pub(crate) fn insert(&mut self, id: u64, analysis: GameAnalysis) {
-        self.red.insert(analysis.max_red, id);
-        self.green.insert(analysis.max_green, id);
-        self.blue.insert(analysis.max_blue, id);
-    }
-
-    pub(crate) fn query(&self, query: GameAnalysis) -> Vec<u64> {
-        let red_results = self.red.query(query.max_red);
-        let green_results = self.green.query(query.max_green);
-        let blue_results = self.blue.query(query.max_blue);
-
-        red_results
-            .intersection(&green_results)
-            .copied()
-            .collect::<HashSet<_>>()
-            .intersection(&blue_results)
-            .copied()
-            .collect::<HashSet<_>>()
+    /// Indexes the game `id`, replacing and returning any analysis already indexed under it.
+    pub fn insert(&mut self, id: u64, analysis: GameAnalysis) -> Option<GameAnalysis> {
+        let previous = self.remove(id);
+        for (color, _) in analysis.maxima() {
+            if !self.colors.contains_key(color) {
+                let mut index = ColorIndex::default();
+                if !self.ids.is_empty() {
+                    index.inner.insert(0, self.ids.clone());
+                }
+                self.colors.insert(color.clone(), index);
+            }
+        }
+        for (color, index) in &mut self.colors {
+            index.insert(analysis.max(color.name()), id);
+        }
+        self.games.insert(id, analysis);
+        self.ids.insert(id);
+        previous
+    }
+
+    /// Replaces the analysis of an indexed game, returning the old one, or does nothing and returns
+    /// `None` if `id` is not indexed.
+    pub fn update(&mut self, id: u64, analysis: GameAnalysis) -> Option<GameAnalysis> {
+        if !self.games.contains_key(&id) {
+            return None;
+        }
+        self.insert(id, analysis)
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<GameAnalysis> {
+        let analysis = self.games.remove(&id)?;
+        for (color, index) in &mut self.colors {
+            index.remove(analysis.max(color.name()), id);
+        }
+        self.ids.remove(id);
+        Some(analysis)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&GameAnalysis> {
+        self.games.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Writes the index in the versioned binary format described in `snapshot`.
+    pub fn save<W>(&self, writer: W) -> Result<()>
+    where
+        W: Write,
+    {
+        snapshot::write(
+            writer,
+            self.colors.keys(),
+            self.games.iter().map(|(&id, analysis)| (id, analysis)),
+        )
+    }
+
+    pub fn load<R>(reader: R) -> Result<Self>
+    where
+        R: Read,
+    {
+        let snapshot = snapshot::read(reader)?;
+        let mut indexed_games = snapshot.games.into_iter().collect::<Self>();
+        for color in snapshot.colors {
+            indexed_games.colors.entry(color).or_insert_with(|| {
+                let mut index = ColorIndex::default();
+                if !indexed_games.ids.is_empty() {
+                    index.inner.insert(0, indexed_games.ids.clone());
+                }
+                index
+            });
+        }
+        Ok(indexed_games)
+    }
+
+    /// Saves to `path` through a uniquely named temporary file in the same directory, which is
+    /// synced to disk and renamed into place before the directory itself is synced. A crash
+    /// mid-write leaves the previous snapshot intact, concurrent saves never share a temporary
+    /// file, and a failed save removes its own.
+    pub fn save_to_file<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut temporary = NamedTempFile::new_in(directory)?;
+        let mut writer = BufWriter::new(&mut temporary);
+        self.save(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        temporary.as_file().sync_all()?;
+        temporary.persist(path).map_err(|error| error.error)?;
+        // Only Unix can open a directory to sync the rename.
+        #[cfg(unix)]
+        File::open(directory)?.sync_all()?;
+        Ok(())
+    }
+
+    pub fn load_from_file<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::load(BufReader::new(File::open(path)?))
+    }
+
+    /// Ids of the games that could have been played with `query` as the bag, in ascending order.
+    /// Colors missing from the bag count as zero cubes.
+    pub fn query(&self, query: GameAnalysis) -> Vec<u64> {
+        self.admitted(&query).into_iter().collect::<Vec<_>>()
+    }
+
+    fn admitted(&self, bag: &GameAnalysis) -> RoaringTreemap {
+        if self.colors.is_empty() {
+            return self.ids.clone();
+        }
+        self.colors
+            .iter()
+            .map(|(color, index)| index.query(bag.max(color.name())))
+            .intersection()
+    }
+
+    /// How many games each of `bags` admits and the sum of their ids, in the order of `bags`. Gives
+    /// the same answers as calling `query` for every bag, but sweeps the index once for all of them;
+    /// that sweep visits every game, so it pays off from a few dozen bags.
+    pub fn query_batch(&self, bags: &[GameAnalysis]) -> Vec<BagCount> {
+        self.query_batch_with(bags, MAX_BATCH_CELLS)
+    }
+
+    fn query_batch_with(&self, bags: &[GameAnalysis], max_cells: usize) -> Vec<BagCount> {
+        let keys = self
+            .colors
+            .iter()
+            .map(|(color, index)| (color, index.inner.keys().copied().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let Some(((first, first_keys), rest)) = keys.split_first() else {
+            return vec![self.count(&GameAnalysis::default()); bags.len()];
+        };
+        let sizes = rest.iter().map(|(_, keys)| keys.len()).collect();
+        let Some(mut fenwick) = Fenwick::new(sizes, max_cells) else {
+            return bags.iter().map(|bag| self.count(bag)).collect();
+        };
+
+        // Every game's coordinates in the remaining colors, grouped by its first color.
+        let mut buckets = vec![Vec::new(); first_keys.len()];
+        let mut points = Vec::with_capacity(self.games.len() * rest.len());
+        for (game, (&id, analysis)) in self.games.iter().enumerate() {
+            let mut maxima = analysis.maxima().peekable();
+            let mut coordinates = keys.iter().map(|(color, keys)| {
+                let max = maxima
+                    .next_if(|&(other, _)| other == *color)
+                    .map_or(0, |(_, max)| max);
+                keys.partition_point(|&key| key < max)
+            });
+            let bucket = coordinates.next().expect("the first color has keys");
+            buckets[bucket].push((game, id));
+            points.extend(coordinates.map(|coordinate| coordinate + 1));
+        }
+
+        let mut order = (0..bags.len()).collect::<Vec<_>>();
+        order.sort_unstable_by_key(|&bag| bags[bag].max(first.name()));
+        let mut swept = first_keys.iter().zip(&buckets).peekable();
+        let mut grid = Vec::new();
+        let mut counts = vec![BagCount::default(); bags.len()];
+        let mut point = vec![0; rest.len()];
+        for bag in order {
+            let limit = bags[bag].max(first.name());
+            while let Some((_, games)) = swept.next_if(|(&key, _)| key <= limit) {
+                let point = |game: usize| &points[game * rest.len()..(game + 1) * rest.len()];
+                // A big first-color bucket is cheaper to add as one grid than game by game.
+                if games.len() < fenwick.len() {
+                    for &(game, id) in games {
+                        fenwick.add(point(game), BagCount::game(id));
+                    }
+                } else {
+                    grid.clear();
+                    grid.resize(fenwick.len(), BagCount::default());
+                    for &(game, id) in games {
+                        grid[fenwick.offset(point(game))] += BagCount::game(id);
+                    }
+                    fenwick.add_grid(&mut grid);
+                }
+            }
+            for (coordinate, (color, keys)) in point.iter_mut().zip(rest) {
+                let max = bags[bag].max(color.name());
+                *coordinate = keys.partition_point(|&key| key <= max);
+            }
+            counts[bag] = fenwick.prefix(&point);
+        }
+        counts
+    }
+
+    fn count(&self, bag: &GameAnalysis) -> BagCount {
+        let ids = self.admitted(bag);
+        BagCount {
+            games: ids.len(),
+            id_sum: ids.iter().map(u128::from).sum(),
+        }
+    }
+
+    /// Every game with its analysis, ordered by `field`.
+    pub fn ranked(&self, field: &Field, order: Order) -> Vec<(u64, &GameAnalysis)> {
+        self.top(field, order, self.len())
+    }
+
+    /// The first `k` games of `ranked`, found without ranking the rest. Colors read off their
+    /// index in order; other fields go through `top_among` over every game.
+    pub fn top(&self, field: &Field, order: Order, k: usize) -> Vec<(u64, &GameAnalysis)> {
+        let ids: Vec<u64> = match (field, order) {
+            (Field::Color(color), Order::Ascending) if self.colors.contains_key(color) => self
+                .colors[color]
+                .inner
+                .values()
+                .flat_map(RoaringTreemap::iter)
+                .take(k)
+                .collect(),
+            (Field::Color(color), Order::Descending) if self.colors.contains_key(color) => self
+                .colors[color]
+                .inner
+                .values()
+                .rev()
+                .flat_map(RoaringTreemap::iter)
+                .take(k)
+                .collect(),
+            _ => return self.top_among(self.games.keys().copied(), field, order, k),
+        };
+        ids.into_iter()
+            .map(|id| (id, &self.games[&id]))
+            .collect::<Vec<_>>()
+    }
+
+    /// The first `k` of the games `ids` names, ordered by `field`, such as the games a query
+    /// selected. Ids that are not indexed are left out.
+    pub fn top_among<I>(
+        &self,
+        ids: I,
+        field: &Field,
+        order: Order,
+        k: usize,
+    ) -> Vec<(u64, &GameAnalysis)>
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        match order {
+            Order::Ascending => self.top_by(ids, k, |id, analysis| field.value(id, analysis)),
+            Order::Descending => {
+                self.top_by(ids, k, |id, analysis| Reverse(field.value(id, analysis)))
+            }
+        }
+    }
+
+    /// The `k` games among `ids` with the smallest `key`, smallest first and ties in ascending id
+    /// order. Holds at most `k` games at a time, so a small `k` costs little memory however many
+    /// ids there are. Ids that are not indexed are left out.
+    pub fn top_by<I, K, F>(&self, ids: I, k: usize, key: F) -> Vec<(u64, &GameAnalysis)>
+    where
+        I: IntoIterator<Item = u64>,
+        K: Ord,
+        F: Fn(u64, &GameAnalysis) -> K,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut top = BinaryHeap::new();
+        for id in ids {
+            let Some(analysis) = self.games.get(&id) else {
+                continue;
+            };
+            let entry = (key(id, analysis), id);
+            if top.len() < k {
+                top.push(entry);
+            } else if let Some(mut largest) = top.peek_mut() {
+                if entry < *largest {
+                    *largest = entry;
+                }
+            }
+        }
+        top.into_sorted_vec()
             .into_iter()
+            .map(|(_, id)| (id, &self.games[&id]))
             .collect::<Vec<_>>()
     }
+
+    /// Ids of the games matching `query`, in ascending order.
+    pub fn select(&self, query: &Query) -> Vec<u64> {
+        self.evaluate(query).into_iter().collect::<Vec<_>>()
+    }
+
+    fn evaluate(&self, query: &Query) -> RoaringTreemap {
+        match query {
+            Query::And(left, right) => self.evaluate(left) & self.evaluate(right),
+            Query::Or(left, right) => self.evaluate(left) | self.evaluate(right),
+            Query::Not(query) => &self.ids - self.evaluate(query),
+            Query::Compare { field, op, value } => match op.bounds(*value) {
+                Some(bounds) => self.field_range(field, bounds),
+                None => {
+                    let bounds = CompareOp::Eq.bounds(*value).expect("== is a range");
+                    &self.ids - self.field_range(field, bounds)
+                }
+            },
+            Query::In { field, start, end } => {
+                self.field_range(field, (Bound::Included(*start), *end))
+            }
+        }
+    }
+
+    fn field_range(&self, field: &Field, bounds: (Bound<u64>, Bound<u64>)) -> RoaringTreemap {
+        let Some(range) = query::inclusive(bounds) else {
+            return RoaringTreemap::new();
+        };
+        match field {
+            Field::Id => {
+                let mut ids = self.ids.clone();
+                ids.remove_range(..*range.start());
+                if *range.end() < u64::MAX {
+                    ids.remove_range(range.end() + 1..);
+                }
+                ids
+            }
+            Field::Power => self
+                .games
+                .iter()
+                .filter(|(_, analysis)| range.contains(&analysis.min_cube()))
+                .map(|(&id, _)| id)
+                .collect(),
+            Field::Color(color) => match self.colors.get(color) {
+                Some(index) => index.range(range),
+                None if range.contains(&0) => self.ids.clone(),
+                None => RoaringTreemap::new(),
+            },
+        }
+    }
+}
+
+impl FromIterator<(u64, GameAnalysis)> for IndexedGames {
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = (u64, GameAnalysis)>,
+    {
+        let mut indexed_games = Self::default();
+        for (id, analysis) in iter {
+            indexed_games.insert(id, analysis);
+        }
+        indexed_games
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorSet;
+    use crate::game::{DuplicatePolicy, Game, GameLog};
+    use std::fs;
+    use test_case::test_case;
+
+    #[test_case("red <= 12 and green <= 13 and blue <= 14", &[1, 2, 5])]
+    #[test_case("red <= 14 and (blue > 5 or green == 2)",   &[1, 4])]
+    #[test_case("power > 500",                              &[3, 4])]
+    #[test_case("power != 48",                              &[2, 3, 4, 5])]
+    #[test_case("id in 2..4",                               &[2, 3])]
+    #[test_case("id in 2..=4 and not red >= 14",            &[2])]
+    #[test_case("id in 4..2",                               &[])]
+    #[test_case("yellow == 0",                              &[1, 2, 3, 4, 5])]
+    #[test_case("yellow > 0",                               &[])]
+    fn test_select(query: &str, expected: &[u64]) {
+        let indexed_games = example();
+        let query = Query::parse(query).unwrap();
+        assert_eq!(indexed_games.select(&query), expected);
+    }
+
+    #[test]
+    fn test_query_backfills_new_colors() {
+        let mut colors = ColorSet::discovering();
+        let first = Game::parse("Game 1: 3 red", &mut colors).unwrap();
+        let mut indexed_games = IndexedGames::default();
+        indexed_games.insert(first.id(), first.analyze(&colors));
+
+        let second = Game::parse("Game 2: 1 red, 2 yellow", &mut colors).unwrap();
+        indexed_games.insert(second.id(), second.analyze(&colors));
+
+        let bag = [(Color::new("red"), 3)].into_iter().collect();
+        assert_eq!(indexed_games.query(bag), [1]);
+
+        let bag = [(Color::new("red"), 3), (Color::new("yellow"), 2)]
+            .into_iter()
+            .collect();
+        let mut result = indexed_games.query(bag);
+        result.sort_unstable();
+        assert_eq!(result, [1, 2]);
+    }
+
+    fn example() -> IndexedGames {
+        crate::example().analyses().collect::<IndexedGames>()
+    }
+
+    fn assert_same_answers(left: &IndexedGames, right: &IndexedGames) {
+        assert_eq!(left.games, right.games);
+        for query in [
+            "red <= 12 and green <= 13 and blue <= 14",
+            "power > 100 or yellow > 0",
+            "not green in 3..=5",
+        ] {
+            let query = Query::parse(query).unwrap();
+            assert_eq!(left.select(&query), right.select(&query));
+        }
+        for (color, index) in &left.colors {
+            assert_eq!(index.inner, right.colors[color].inner, "{color}");
+        }
+    }
+
+    #[test]
+    fn test_remove_and_update() {
+        let mut indexed_games = example();
+        assert_eq!(indexed_games.remove(3), Some(GameAnalysis::new(20, 13, 6)));
+        assert_eq!(indexed_games.remove(3), None);
+        assert_eq!(
+            indexed_games.update(4, GameAnalysis::new(1, 1, 1)),
+            Some(GameAnalysis::new(14, 3, 15))
+        );
+        assert_eq!(indexed_games.update(9, GameAnalysis::new(1, 1, 1)), None);
+
+        let mut expected = example();
+        expected.games.clear();
+        expected.colors.clear();
+        expected.ids.clear();
+        for (id, analysis) in example().games {
+            match id {
+                3 => {}
+                4 => {
+                    expected.insert(id, GameAnalysis::new(1, 1, 1));
+                }
+                _ => {
+                    expected.insert(id, analysis);
+                }
+            }
+        }
+        assert_eq!(indexed_games.len(), 4);
+        assert_same_answers(&indexed_games, &expected);
+    }
+
+    #[test]
+    fn test_insert_replaces() {
+        let mut indexed_games = example();
+        indexed_games.insert(1, GameAnalysis::new(30, 0, 0));
+        let query = Query::parse("red <= 12").unwrap();
+        assert_eq!(indexed_games.select(&query), [2, 5]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut colors = ColorSet::discovering();
+        let mut indexed_games = example();
+        let game = Game::parse("Game 6: 2 yellow, 300 red", &mut colors).unwrap();
+        indexed_games.insert(game.id(), game.analyze(&colors));
+
+        let mut bytes = Vec::new();
+        indexed_games.save(&mut bytes).unwrap();
+        let loaded = IndexedGames::load(bytes.as_slice()).unwrap();
+        assert_same_answers(&loaded, &indexed_games);
+    }
+
+    #[test]
+    fn test_save_and_load_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let indexed_games = example();
+        for name in ["games.idx", "games.tmp"] {
+            let path = directory.path().join(name);
+            indexed_games.save_to_file(&path).unwrap();
+            let loaded = IndexedGames::load_from_file(&path).unwrap();
+            assert_same_answers(&loaded, &indexed_games);
+        }
+
+        let unrelated = directory.path().join("games.tmp");
+        let before = fs::read(&unrelated).unwrap();
+        indexed_games
+            .save_to_file(directory.path().join("games.idx"))
+            .unwrap();
+        assert_eq!(fs::read(&unrelated).unwrap(), before);
+        assert_eq!(entries(directory.path()), ["games.idx", "games.tmp"]);
+    }
+
+    #[test]
+    fn test_failed_save_leaves_no_temporary_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let occupied = directory.path().join("games.idx");
+        fs::create_dir(&occupied).unwrap();
+        assert!(example().save_to_file(&occupied).is_err());
+        assert_eq!(entries(directory.path()), ["games.idx"]);
+    }
+
+    fn entries(directory: &Path) -> Vec<String> {
+        let mut names = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test_case(b"AOCGAMEX\x01\x00\x00\x00",                 "game index: not a game index"                  ; "magic")]
+    #[test_case(b"AOCGAMES\x02\x00\x00\x00",                 "game index: unsupported version 2, expected 1" ; "version")]
+    #[test_case(b"AOCGAMES\x01\x00\x01\x05re",               "game index: truncated"                         ; "truncated")]
+    #[test_case(b"AOCGAMES\x01\x00\x00\x01\x07\x01\x00\x01", "game index: game 7: no color number 0"         ; "color")]
+    fn test_load_errors(bytes: &[u8], expected: &str) {
+        let result = IndexedGames::load(bytes);
+        assert!(
+            matches!(&result, Err(aoc_common::Error::InvalidInput(message)) if message == expected),
+            "{result:?}"
+        );
+    }
+
+    #[test_case(MAX_BATCH_CELLS)]
+    #[test_case(0)]
+    fn test_query_batch(max_cells: usize) {
+        let mut colors = ColorSet::discovering();
+        let mut indexed_games = example();
+        let game = Game::parse("Game 6: 2 yellow, 3 red", &mut colors).unwrap();
+        indexed_games.insert(game.id(), game.analyze(&colors));
+
+        let mut bags = vec![
+            GameAnalysis::new(12, 13, 14),
+            GameAnalysis::new(0, 0, 0),
+            GameAnalysis::new(20, 13, 15),
+            GameAnalysis::new(6, 3, 6),
+            [(Color::new("red"), 4), (Color::new("yellow"), 2)]
+                .into_iter()
+                .collect(),
+        ];
+        for (bag, yellow) in bags.clone().into_iter().zip([2, 0, 1, 5, 2]) {
+            let bag = bag
+                .maxima()
+                .map(|(color, max)| (color.clone(), max))
+                .chain([(Color::new("yellow"), yellow)])
+                .collect();
+            bags.push(bag);
+        }
+
+        let expected = bags
+            .iter()
+            .map(|bag| {
+                let ids = indexed_games.query(bag.clone());
+                BagCount {
+                    games: ids.len() as u64,
+                    id_sum: ids.iter().copied().map(u128::from).sum(),
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(indexed_games.query_batch_with(&bags, max_cells), expected);
+        assert_eq!(
+            expected[5],
+            BagCount {
+                games: 4,
+                id_sum: 14
+            }
+        );
+    }
+
+    #[test]
+    fn test_query_batch_without_colors() {
+        let indexed_games = [(3, GameAnalysis::default()), (4, GameAnalysis::default())]
+            .into_iter()
+            .collect::<IndexedGames>();
+        assert_eq!(
+            indexed_games.query_batch(&[GameAnalysis::new(1, 1, 1)]),
+            [BagCount {
+                games: 2,
+                id_sum: 7
+            }]
+        );
+        assert_eq!(IndexedGames::default().query_batch(&[]), []);
+    }
+
+    #[test_case(MAX_BATCH_CELLS)]
+    #[test_case(0)]
+    fn test_query_batch_large_ids(max_cells: usize) {
+        let indexed_games = [
+            (u64::MAX, GameAnalysis::new(1, 1, 1)),
+            (2, GameAnalysis::new(1, 1, 1)),
+        ]
+        .into_iter()
+        .collect::<IndexedGames>();
+        let expected = BagCount {
+            games: 2,
+            id_sum: u128::from(u64::MAX) + 2,
+        };
+        assert_eq!(
+            indexed_games.query_batch_with(&[GameAnalysis::new(1, 1, 1)], max_cells),
+            [expected]
+        );
+    }
+
+    #[test_case("red",    Order::Descending, 2, &[3, 4])]
+    #[test_case("red",    Order::Ascending,  3, &[2, 1, 5])]
+    #[test_case("green",  Order::Descending, 5, &[3, 2, 4, 5, 1])]
+    #[test_case("power",  Order::Descending, 3, &[3, 4, 1])]
+    #[test_case("power",  Order::Ascending,  9, &[2, 5, 1, 4, 3])]
+    #[test_case("id",     Order::Descending, 2, &[5, 4])]
+    #[test_case("yellow", Order::Descending, 2, &[1, 2])]
+    #[test_case("blue",   Order::Ascending,  0, &[])]
+    fn test_top(field: &str, order: Order, k: usize, expected: &[u64]) {
+        let indexed_games = example();
+        let field = field.parse::<Field>().unwrap();
+        let top = indexed_games.top(&field, order, k);
+        assert_eq!(top.iter().map(|&(id, _)| id).collect::<Vec<_>>(), expected);
+        for (id, analysis) in top {
+            assert_eq!(indexed_games.get(id), Some(analysis));
+        }
+    }
+
+    #[test]
+    fn test_ranked_matches_top() {
+        let indexed_games = example();
+        for field in ["red", "green", "blue", "power", "id", "yellow"] {
+            let field = field.parse::<Field>().unwrap();
+            for order in [Order::Ascending, Order::Descending] {
+                let ranked = indexed_games.ranked(&field, order);
+                assert_eq!(ranked.len(), 5);
+                for k in 0..=6 {
+                    let expected = &ranked[..k.min(ranked.len())];
+                    assert_eq!(
+                        indexed_games.top(&field, order, k),
+                        expected,
+                        "{field:?} {k}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test_case("power", Order::Descending, 2, &[1, 5])]
+    #[test_case("red",   Order::Ascending,  2, &[2, 1])]
+    #[test_case("blue",  Order::Descending, 9, &[1, 2, 5])]
+    #[test_case("id",    Order::Ascending,  0, &[])]
+    fn test_top_among_selected(field: &str, order: Order, k: usize, expected: &[u64]) {
+        let indexed_games = example();
+        let selected = indexed_games.select(&Query::parse("red <= 12 and green <= 13").unwrap());
+        let field = field.parse::<Field>().unwrap();
+        let top = indexed_games.top_among(selected, &field, order, k);
+        assert_eq!(top.iter().map(|&(id, _)| id).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_top_by_key() {
+        let indexed_games = example();
+        let top = indexed_games.top_by([5, 4, 3, 2, 1, 99], 3, |id, _| id % 2);
+        assert_eq!(top.iter().map(|&(id, _)| id).collect::<Vec<_>>(), [2, 4, 1]);
+    }
+
+    #[test_case(DuplicatePolicy::Sum, &[1, 2])]
+    #[test_case(DuplicatePolicy::Max, &[2])]
+    fn test_query_duplicate_colors(duplicates: DuplicatePolicy, expected: &[u64]) {
+        let log = "Game 1: 2 red, 2 red\nGame 2: 3 red\n";
+        let log = GameLog::parse(log.as_bytes(), ColorSet::rgb(), duplicates).unwrap();
+        let indexed_games = log.analyses().collect::<IndexedGames>();
+        let query = Query::parse("red >= 3").unwrap();
+        assert_eq!(indexed_games.select(&query), expected);
+    }
 }
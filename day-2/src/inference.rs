@@ -0,0 +1,326 @@
+//! What a game log says about the bag the games were played with.
+//!
+//! Bags are `GameAnalysis` values: a bag admits a game when it holds at least the game's maximum of
+//! every color.
+
+use crate::color::{Color, ColorSet};
+use crate::game::{Game, GameAnalysis, GameLog};
+
+/// The games of a log as rows of per-color maxima, in `ColorSet` order.
+#[derive(Debug)]
+pub struct BagInference<'a> {
+    colors: Vec<Color>,
+    games: Vec<&'a Game>,
+    maxima: Vec<Vec<u64>>,
+}
+
+impl<'a> BagInference<'a> {
+    pub fn new(log: &'a GameLog) -> Self {
+        Self::for_games(&log.games, &log.colors)
+    }
+
+    /// Inference over just `games`, for example the ones a query selected.
+    pub fn for_games<I>(games: I, colors: &ColorSet) -> Self
+    where
+        I: IntoIterator<Item = &'a Game>,
+    {
+        let games = games.into_iter().collect::<Vec<_>>();
+        let maxima = games
+            .iter()
+            .map(|game| {
+                let analysis = game.analyze(colors);
+                colors
+                    .iter()
+                    .map(|color| analysis.max(color.name()))
+                    .collect()
+            })
+            .collect();
+        Self {
+            colors: colors.iter().cloned().collect(),
+            games,
+            maxima,
+        }
+    }
+
+    /// Every bag that admits at least `min_games` of the games and that no smaller bag (with fewer
+    /// or equal cubes of every color) can replace. With `min_games` equal to the number of games
+    /// this is the single bag of the per-color maxima over the whole log.
+    ///
+    /// The search walks every combination of the games' per-color maxima, so it is exponential in
+    /// the number of colors; it is meant for the handful of colors logs actually use.
+    pub fn pareto_frontier(&self, min_games: usize) -> Vec<GameAnalysis> {
+        if min_games > self.maxima.len() {
+            return Vec::new();
+        }
+
+        let candidates = (0..self.colors.len())
+            .map(|color| {
+                let mut values = self.maxima.iter().map(|row| row[color]).collect::<Vec<_>>();
+                values.push(0);
+                values.sort_unstable();
+                values.dedup();
+                values
+            })
+            .collect::<Vec<_>>();
+
+        let mut frontier = Vec::new();
+        let admitted = (0..self.maxima.len()).collect::<Vec<_>>();
+        let mut bag = Vec::with_capacity(self.colors.len());
+        self.walk(&candidates, min_games, &admitted, &mut bag, &mut frontier);
+        frontier
+    }
+
+    fn walk(
+        &self,
+        candidates: &[Vec<u64>],
+        min_games: usize,
+        admitted: &[usize],
+        bag: &mut Vec<u64>,
+        frontier: &mut Vec<GameAnalysis>,
+    ) {
+        if admitted.len() < min_games {
+            return;
+        }
+        let color = bag.len();
+        if color == self.colors.len() {
+            if self.is_minimal(candidates, min_games, bag) {
+                frontier.push(self.to_analysis(bag));
+            }
+            return;
+        }
+
+        for &value in &candidates[color] {
+            let admitted = admitted
+                .iter()
+                .copied()
+                .filter(|&game| self.maxima[game][color] <= value)
+                .collect::<Vec<_>>();
+            bag.push(value);
+            self.walk(candidates, min_games, &admitted, bag, frontier);
+            bag.pop();
+        }
+    }
+
+    /// Whether lowering any one color of `bag` to the next smaller candidate would admit too few
+    /// games. Admission only shrinks as a bag shrinks, so this is enough to rule out every
+    /// smaller bag.
+    fn is_minimal(&self, candidates: &[Vec<u64>], min_games: usize, bag: &[u64]) -> bool {
+        (0..bag.len()).all(|color| {
+            let Some(&lower) = candidates[color].iter().rev().find(|&&v| v < bag[color]) else {
+                return true;
+            };
+            let admitted = self
+                .maxima
+                .iter()
+                .filter(|row| {
+                    row.iter()
+                        .enumerate()
+                        .all(|(c, &max)| max <= if c == color { lower } else { bag[c] })
+                })
+                .count();
+            admitted < min_games
+        })
+    }
+
+    /// The bag with the fewest cubes in total that admits at least `min_games` of the games, or
+    /// `None` if there are fewer games than that.
+    pub fn smallest_bag(&self, min_games: usize) -> Option<GameAnalysis> {
+        self.pareto_frontier(min_games)
+            .into_iter()
+            .min_by_key(|bag| bag.maxima().map(|(_, count)| count).sum::<u64>())
+    }
+
+    /// The bag most likely to have produced every draw of the games, taking each draw as a handful
+    /// pulled without replacement, so that its chance is multivariate hypergeometric:
+    ///
+    /// ```text
+    /// P(k₁…kₘ | N₁…Nₘ) = C(N₁, k₁) ⋯ C(Nₘ, kₘ) / C(N₁ + ⋯ + Nₘ, k₁ + ⋯ + kₘ)
+    /// ```
+    ///
+    /// Larger bags can keep getting (slightly) more likely, so every color is capped at
+    /// `max_per_color`. The search is cyclic coordinate ascent from the smallest admitting bag:
+    /// each color in turn is set to its best count with the others held fixed, until nothing
+    /// improves. Returns the bag with its log-likelihood, or `None` if a draw shows more than
+    /// `max_per_color` cubes of a color.
+    pub fn maximum_likelihood_bag(&self, max_per_color: u64) -> Option<(GameAnalysis, f64)> {
+        let draws = self
+            .games
+            .iter()
+            .flat_map(|game| game.iterations())
+            .map(|iteration| {
+                self.colors
+                    .iter()
+                    .map(|color| iteration.count(color.name()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut bag = (0..self.colors.len())
+            .map(|color| self.maxima.iter().map(|row| row[color]).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+        if bag.iter().any(|&count| count > max_per_color) {
+            return None;
+        }
+
+        let floor = bag.clone();
+        let mut best = log_likelihood(&draws, &bag);
+        loop {
+            let mut improved = false;
+            for color in 0..bag.len() {
+                let mut best_count = bag[color];
+                for count in floor[color]..=max_per_color {
+                    bag[color] = count;
+                    let likelihood = log_likelihood(&draws, &bag);
+                    if likelihood > best {
+                        best = likelihood;
+                        best_count = count;
+                        improved = true;
+                    }
+                }
+                bag[color] = best_count;
+            }
+            if !improved {
+                break;
+            }
+        }
+
+        Some((self.to_analysis(&bag), best))
+    }
+
+    fn to_analysis(&self, bag: &[u64]) -> GameAnalysis {
+        self.colors
+            .iter()
+            .cloned()
+            .zip(bag.iter().copied())
+            .collect()
+    }
+}
+
+/// `ln C(n, k)`, or negative infinity when `k > n`.
+fn ln_choose(n: u64, k: u64) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (1..=k)
+        .map(|i| ((n - k + i) as f64).ln() - (i as f64).ln())
+        .sum()
+}
+
+fn log_likelihood(draws: &[Vec<u64>], bag: &[u64]) -> f64 {
+    let total = bag.iter().sum::<u64>();
+    draws
+        .iter()
+        .map(|draw| {
+            let shown = draw.iter().sum::<u64>();
+            draw.iter()
+                .zip(bag)
+                .map(|(&count, &cubes)| ln_choose(cubes, count))
+                .sum::<f64>()
+                - ln_choose(total, shown)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example;
+    use test_case::test_case;
+
+    #[test_case(0, Some(GameAnalysis::new(0, 0, 0)))]
+    #[test_case(1, Some(GameAnalysis::new(1, 3, 4)))]
+    #[test_case(3, Some(GameAnalysis::new(6, 3, 6)))]
+    #[test_case(5, Some(GameAnalysis::new(20, 13, 15)))]
+    #[test_case(6, None)]
+    fn test_smallest_bag(min_games: usize, expected: Option<GameAnalysis>) {
+        let log = example();
+        let inference = BagInference::new(&log);
+        assert_eq!(inference.smallest_bag(min_games), expected);
+    }
+
+    #[test]
+    fn test_pareto_frontier() {
+        let log = example();
+        let inference = BagInference::new(&log);
+
+        let mut frontier = inference.pareto_frontier(1);
+        frontier.sort_by_key(|bag| bag.max("red"));
+        assert_eq!(
+            frontier,
+            [
+                GameAnalysis::new(1, 3, 4),
+                GameAnalysis::new(4, 2, 6),
+                GameAnalysis::new(6, 3, 2),
+            ]
+        );
+
+        assert_eq!(
+            inference.pareto_frontier(5),
+            [GameAnalysis::new(20, 13, 15)]
+        );
+    }
+
+    #[test]
+    fn test_pareto_frontier_of_selected_games() {
+        let log = example();
+        let selected = log.games.iter().filter(|game| [3, 4].contains(&game.id()));
+        let inference = BagInference::for_games(selected, &log.colors);
+        let mut frontier = inference.pareto_frontier(1);
+        frontier.sort_by_key(|bag| bag.max("red"));
+        assert_eq!(
+            frontier,
+            [GameAnalysis::new(14, 3, 15), GameAnalysis::new(20, 13, 6),]
+        );
+    }
+
+    #[test]
+    fn test_maximum_likelihood_bag_matches_exhaustive_search() {
+        let log = example();
+        let games = log.games.iter().filter(|game| game.id() == 1);
+        let inference = BagInference::for_games(games, &log.colors);
+        let (bag, likelihood) = inference.maximum_likelihood_bag(12).unwrap();
+
+        let game = &log.games[0];
+        let draws = game
+            .iterations()
+            .iter()
+            .map(|iteration| {
+                log.colors
+                    .iter()
+                    .map(|color| iteration.count(color.name()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut best = f64::NEG_INFINITY;
+        for blue in 6..=12 {
+            for green in 2..=12 {
+                for red in 4..=12 {
+                    best = best.max(log_likelihood(&draws, &[blue, green, red]));
+                }
+            }
+        }
+        assert!((likelihood - best).abs() < 1e-9, "{likelihood} != {best}");
+        assert_eq!(
+            log_likelihood(&draws, &[bag.max("blue"), bag.max("green"), bag.max("red")]),
+            likelihood
+        );
+    }
+
+    #[test]
+    fn test_maximum_likelihood_bag_respects_cap() {
+        let log = example();
+        let inference = BagInference::new(&log);
+        assert_eq!(inference.maximum_likelihood_bag(19), None);
+        let (bag, _) = inference.maximum_likelihood_bag(25).unwrap();
+        assert!(bag.max("red") >= 20 && bag.max("blue") >= 15 && bag.max("green") >= 13);
+    }
+
+    #[test_case(5, 2, 10.0_f64.ln())]
+    #[test_case(4, 0, 0.0)]
+    #[test_case(2, 3, f64::NEG_INFINITY)]
+    fn test_ln_choose(n: u64, k: u64, expected: f64) {
+        let result = ln_choose(n, k);
+        assert!(result == expected || (result - expected).abs() < 1e-12);
+    }
+}
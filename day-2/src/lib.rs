@@ -0,0 +1,55 @@
+pub mod batch;
+pub mod color;
+pub mod error;
+pub mod game;
+pub mod index;
+pub mod inference;
+pub mod query;
+mod snapshot;
+
+use std::io::BufRead;
+
+use aoc_common::{input_path, Result, Solution};
+
+use color::ColorSet;
+use game::{DuplicatePolicy, GameAnalysis, GameLog};
+use index::IndexedGames;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const FILES_DIR: &'static str = input_path!("files");
+
+    type Input = GameLog;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse<R>(reader: R) -> Result<Self::Input>
+    where
+        R: BufRead,
+    {
+        GameLog::parse(reader, ColorSet::rgb(), DuplicatePolicy::default())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        let query = GameAnalysis::new(12, 13, 14);
+        let indexed_games = input.analyses().collect::<IndexedGames>();
+
+        Ok(indexed_games.query(query).iter().sum::<u64>())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(input
+            .analyses()
+            .map(|(_, analysis)| analysis.min_cube())
+            .sum())
+    }
+}
+
+/// The example fixture in `files/test.txt`, for the modules' tests.
+#[cfg(test)]
+fn example() -> GameLog {
+    let reader = aoc_common::Input::Example.open::<Day2>().unwrap();
+    Day2::parse(reader).unwrap()
+}
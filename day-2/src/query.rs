@@ -0,0 +1,505 @@
+//! A small expression language over game analyses, for example
+//! `red <= 12 and (blue > 3 or green == 0)`, `power > 500` or `id in 10..50`.
+//!
+//! ```text
+//! query      := or
+//! or         := and ("or" and)*
+//! and        := not ("and" not)*
+//! not        := "not" not | "(" query ")" | comparison
+//! comparison := field op number | field "in" number (".." | "..=") number
+//! field      := "id" | "power" | color
+//! op         := "<" | "<=" | ">" | ">=" | "==" | "!="
+//! ```
+//!
+//! Any other identifier is a color; a color the log never mentions counts as zero everywhere.
+//! Parentheses and `not` nest at most `MAX_DEPTH` deep.
+
+use std::fmt;
+use std::ops::{Bound, RangeInclusive};
+use std::str::FromStr;
+
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::color::Color;
+use crate::game::GameAnalysis;
+
+/// How deep parentheses and `not` may nest, so a hostile query cannot overflow the stack.
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Display, Error, Eq, PartialEq)]
+pub enum QueryError {
+    /// Unexpected character {found:?} at offset {offset}
+    UnexpectedCharacter { offset: usize, found: char },
+    /// Expected {expected} at offset {offset}, found {found:?}
+    UnexpectedToken {
+        offset: usize,
+        found: String,
+        expected: &'static str,
+    },
+    /// Expected {expected} at the end of the query
+    UnexpectedEnd { expected: &'static str },
+    /// Number {token:?} at offset {offset} is out of range
+    InvalidNumber { offset: usize, token: String },
+    /// Query nests more than {max} deep at offset {offset}
+    TooDeep { offset: usize, max: usize },
+}
+
+impl From<QueryError> for aoc_common::Error {
+    fn from(error: QueryError) -> Self {
+        Self::InvalidArgument(error.to_string())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Field {
+    Id,
+    /// The product of a game's color maxima.
+    Power,
+    /// A game's maximum of one color.
+    Color(Color),
+}
+
+impl Field {
+    /// The field's value for the game `id`.
+    pub fn value(&self, id: u64, analysis: &GameAnalysis) -> u64 {
+        match self {
+            Self::Id => id,
+            Self::Power => analysis.min_cube(),
+            Self::Color(color) => analysis.max(color.name()),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = QueryError;
+
+    /// Parses a single `field` of the grammar, such as `power` or `red`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(lex(s)?);
+        let field = parser.expect("a field")?.field("a field")?;
+        match parser.next() {
+            None => Ok(field),
+            Some(token) => Err(token.unexpected("the end of the field")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    /// The values that satisfy `op value`, or `None` for `!=`, which is not a single range.
+    pub fn bounds(self, value: u64) -> Option<(Bound<u64>, Bound<u64>)> {
+        Some(match self {
+            Self::Lt => (Bound::Unbounded, Bound::Excluded(value)),
+            Self::Le => (Bound::Unbounded, Bound::Included(value)),
+            Self::Gt => (Bound::Excluded(value), Bound::Unbounded),
+            Self::Ge => (Bound::Included(value), Bound::Unbounded),
+            Self::Eq => (Bound::Included(value), Bound::Included(value)),
+            Self::Ne => return None,
+        })
+    }
+}
+
+/// The values in `bounds` as an inclusive range, or `None` if there are none. Unlike
+/// `BTreeMap::range`, this accepts reversed bounds such as `50..10`.
+pub(crate) fn inclusive(bounds: (Bound<u64>, Bound<u64>)) -> Option<RangeInclusive<u64>> {
+    let start = match bounds.0 {
+        Bound::Included(start) => start,
+        Bound::Excluded(start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match bounds.1 {
+        Bound::Included(end) => end,
+        Bound::Excluded(end) => end.checked_sub(1)?,
+        Bound::Unbounded => u64::MAX,
+    };
+    (start <= end).then_some(start..=end)
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Compare {
+        field: Field,
+        op: CompareOp,
+        value: u64,
+    },
+    In {
+        field: Field,
+        start: u64,
+        end: Bound<u64>,
+    },
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser::new(lex(query)?);
+        let result = parser.or()?;
+        match parser.next() {
+            None => Ok(result),
+            Some(token) => Err(token.unexpected("`and`, `or` or the end of the query")),
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Number(u64),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Range,
+    RangeInclusive,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(ident) => f.write_str(ident),
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Op(op) => f.write_str(match op {
+                CompareOp::Lt => "<",
+                CompareOp::Le => "<=",
+                CompareOp::Gt => ">",
+                CompareOp::Ge => ">=",
+                CompareOp::Eq => "==",
+                CompareOp::Ne => "!=",
+            }),
+            Self::LParen => f.write_str("("),
+            Self::RParen => f.write_str(")"),
+            Self::Range => f.write_str(".."),
+            Self::RangeInclusive => f.write_str("..="),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    offset: usize,
+    kind: TokenKind,
+}
+
+impl Token {
+    fn unexpected(&self, expected: &'static str) -> QueryError {
+        QueryError::UnexpectedToken {
+            offset: self.offset,
+            found: self.kind.to_string(),
+            expected,
+        }
+    }
+
+    fn field(&self, expected: &'static str) -> Result<Field, QueryError> {
+        match &self.kind {
+            TokenKind::Ident(ident) => match ident.as_str() {
+                "and" | "or" | "in" | "not" => Err(self.unexpected(expected)),
+                "id" => Ok(Field::Id),
+                "power" => Ok(Field::Power),
+                color => Ok(Field::Color(Color::new(color))),
+            },
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+}
+
+fn lex(query: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some(&(offset, c)) = chars.peek() {
+        let kind = if c.is_whitespace() {
+            chars.next();
+            continue;
+        } else if c.is_ascii_digit() {
+            let mut end = offset;
+            while let Some(&(index, c)) = chars.peek() {
+                if !c.is_ascii_digit() {
+                    break;
+                }
+                end = index + c.len_utf8();
+                chars.next();
+            }
+            let token = &query[offset..end];
+            TokenKind::Number(token.parse().map_err(|_| QueryError::InvalidNumber {
+                offset,
+                token: token.to_string(),
+            })?)
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = offset;
+            while let Some(&(index, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_' || c == '-') {
+                    break;
+                }
+                end = index + c.len_utf8();
+                chars.next();
+            }
+            TokenKind::Ident(query[offset..end].to_string())
+        } else {
+            chars.next();
+            let next = chars.peek().map(|&(_, c)| c);
+            let (kind, pair) = match (c, next) {
+                ('(', _) => (TokenKind::LParen, false),
+                (')', _) => (TokenKind::RParen, false),
+                ('<', Some('=')) => (TokenKind::Op(CompareOp::Le), true),
+                ('<', _) => (TokenKind::Op(CompareOp::Lt), false),
+                ('>', Some('=')) => (TokenKind::Op(CompareOp::Ge), true),
+                ('>', _) => (TokenKind::Op(CompareOp::Gt), false),
+                ('=', Some('=')) => (TokenKind::Op(CompareOp::Eq), true),
+                ('!', Some('=')) => (TokenKind::Op(CompareOp::Ne), true),
+                ('.', Some('.')) => {
+                    chars.next();
+                    if chars.next_if(|&(_, c)| c == '=').is_some() {
+                        (TokenKind::RangeInclusive, false)
+                    } else {
+                        (TokenKind::Range, false)
+                    }
+                }
+                _ => return Err(QueryError::UnexpectedCharacter { offset, found: c }),
+            };
+            if pair {
+                chars.next();
+            }
+            kind
+        };
+        tokens.push(Token { offset, kind });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// How many parentheses and `not`s enclose the current position.
+    depth: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &'static str) -> Result<Token, QueryError> {
+        self.next().ok_or(QueryError::UnexpectedEnd { expected })
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(
+            self.peek(),
+            Some(Token { kind: TokenKind::Ident(ident), .. }) if ident == keyword
+        );
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Parses `parse` one level deeper, failing at `offset` if that is too deep.
+    fn nested<T>(
+        &mut self,
+        offset: usize,
+        parse: impl FnOnce(&mut Self) -> Result<T, QueryError>,
+    ) -> Result<T, QueryError> {
+        if self.depth == MAX_DEPTH {
+            return Err(QueryError::TooDeep {
+                offset,
+                max: MAX_DEPTH,
+            });
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn number(&mut self) -> Result<u64, QueryError> {
+        let token = self.expect("a number")?;
+        match token.kind {
+            TokenKind::Number(number) => Ok(number),
+            _ => Err(token.unexpected("a number")),
+        }
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.and()?;
+        while self.keyword("or") {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.not()?;
+        while self.keyword("and") {
+            query = Query::And(Box::new(query), Box::new(self.not()?));
+        }
+        Ok(query)
+    }
+
+    fn not(&mut self) -> Result<Query, QueryError> {
+        let offset = self.peek().map_or(0, |token| token.offset);
+        if self.keyword("not") {
+            let query = self.nested(offset, Self::not)?;
+            return Ok(Query::Not(Box::new(query)));
+        }
+
+        let token = self.expect("a field, `not` or `(`")?;
+        let field = match token.kind {
+            TokenKind::LParen => {
+                let query = self.nested(token.offset, Self::or)?;
+                let token = self.expect("`)`")?;
+                return match token.kind {
+                    TokenKind::RParen => Ok(query),
+                    _ => Err(token.unexpected("`)`")),
+                };
+            }
+            _ => token.field("a field, `not` or `(`")?,
+        };
+
+        if self.keyword("in") {
+            let start = self.number()?;
+            let token = self.expect("`..` or `..=`")?;
+            let end = match token.kind {
+                TokenKind::Range => Bound::Excluded(self.number()?),
+                TokenKind::RangeInclusive => Bound::Included(self.number()?),
+                _ => return Err(token.unexpected("`..` or `..=`")),
+            };
+            return Ok(Query::In { field, start, end });
+        }
+
+        let token = self.expect("a comparison or `in`")?;
+        let op = match token.kind {
+            TokenKind::Op(op) => op,
+            _ => return Err(token.unexpected("a comparison or `in`")),
+        };
+        let value = self.number()?;
+        Ok(Query::Compare { field, op, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn compare(field: &str, op: CompareOp, value: u64) -> Query {
+        let field = match field {
+            "id" => Field::Id,
+            "power" => Field::Power,
+            color => Field::Color(Color::new(color)),
+        };
+        Query::Compare { field, op, value }
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let result = Query::parse("red <= 12 and (blue > 3 or green == 0)").unwrap();
+        assert_eq!(
+            result,
+            Query::And(
+                Box::new(compare("red", CompareOp::Le, 12)),
+                Box::new(Query::Or(
+                    Box::new(compare("blue", CompareOp::Gt, 3)),
+                    Box::new(compare("green", CompareOp::Eq, 0)),
+                )),
+            )
+        );
+
+        let result = Query::parse("not power > 500 or id != 3 and yellow < 1").unwrap();
+        assert_eq!(
+            result,
+            Query::Or(
+                Box::new(Query::Not(Box::new(compare("power", CompareOp::Gt, 500)))),
+                Box::new(Query::And(
+                    Box::new(compare("id", CompareOp::Ne, 3)),
+                    Box::new(compare("yellow", CompareOp::Lt, 1)),
+                )),
+            )
+        );
+    }
+
+    #[test_case("id in 10..50",  Bound::Excluded(50))]
+    #[test_case("id in 10..=50", Bound::Included(50))]
+    fn test_parse_range(query: &str, end: Bound<u64>) {
+        let result = Query::parse(query).unwrap();
+        assert_eq!(
+            result,
+            Query::In {
+                field: Field::Id,
+                start: 10,
+                end,
+            }
+        );
+    }
+
+    #[test_case("red <= ",                    QueryError::UnexpectedEnd { expected: "a number" })]
+    #[test_case("red & 1",                    QueryError::UnexpectedCharacter { offset: 4, found: '&' })]
+    #[test_case("red 12",                     QueryError::UnexpectedToken { offset: 4, found: "12".to_string(), expected: "a comparison or `in`" })]
+    #[test_case("(red < 1",                   QueryError::UnexpectedEnd { expected: "`)`" })]
+    #[test_case("red < 1 blue",               QueryError::UnexpectedToken { offset: 8, found: "blue".to_string(), expected: "`and`, `or` or the end of the query" })]
+    #[test_case("id in 1...2",                QueryError::UnexpectedCharacter { offset: 9, found: '.' })]
+    #[test_case("red < 99999999999999999999", QueryError::InvalidNumber { offset: 6, token: "99999999999999999999".to_string() })]
+    fn test_parse_errors(query: &str, expected: QueryError) {
+        assert_eq!(Query::parse(query), Err(expected));
+    }
+
+    #[test_case("(",    ")", 1 ; "parentheses")]
+    #[test_case("not ", "",  4 ; "not")]
+    fn test_parse_depth(open: &str, close: &str, width: usize) {
+        let nest = |depth| format!("{}red < 1{}", open.repeat(depth), close.repeat(depth));
+        assert!(Query::parse(&nest(MAX_DEPTH)).is_ok());
+        assert_eq!(
+            Query::parse(&nest(MAX_DEPTH + 1)),
+            Err(QueryError::TooDeep {
+                offset: MAX_DEPTH * width,
+                max: MAX_DEPTH,
+            })
+        );
+        assert!(matches!(
+            Query::parse(&nest(100_000)),
+            Err(QueryError::TooDeep { .. })
+        ));
+    }
+
+    #[test_case("power", Ok(Field::Power))]
+    #[test_case(" red ", Ok(Field::Color(Color::new("red"))))]
+    #[test_case("and",   Err(QueryError::UnexpectedToken { offset: 0, found: "and".to_string(), expected: "a field" }))]
+    #[test_case("red 1", Err(QueryError::UnexpectedToken { offset: 4, found: "1".to_string(), expected: "the end of the field" }))]
+    #[test_case("",      Err(QueryError::UnexpectedEnd { expected: "a field" }))]
+    fn test_parse_field(field: &str, expected: Result<Field, QueryError>) {
+        assert_eq!(field.parse::<Field>(), expected);
+    }
+}
@@ -0,0 +1,174 @@
+//! The on-disk form of an `IndexedGames`:
+//!
+//! ```text
+//! magic    b"AOCGAMES"
+//! version  u16, little endian
+//! colors   varint count, then per color a varint byte length and the UTF-8 name
+//! games    varint count, then per game its varint id, a varint entry count and per entry a
+//!          varint color number (into the colors above) and varint maximum
+//! ```
+//!
+//! Varints are unsigned LEB128. Only the games are stored; the bitmaps are rebuilt on load.
+
+use std::io::{ErrorKind, Read, Write};
+
+use aoc_common::{Error, Result};
+
+use crate::color::Color;
+use crate::game::GameAnalysis;
+
+const MAGIC: &[u8; 8] = b"AOCGAMES";
+pub(crate) const VERSION: u16 = 1;
+
+/// What a snapshot file holds.
+pub(crate) struct Snapshot {
+    pub(crate) colors: Vec<Color>,
+    pub(crate) games: Vec<(u64, GameAnalysis)>,
+}
+
+pub(crate) fn write<'a, W, C, G>(mut writer: W, colors: C, games: G) -> Result<()>
+where
+    W: Write,
+    C: ExactSizeIterator<Item = &'a Color>,
+    G: ExactSizeIterator<Item = (u64, &'a GameAnalysis)>,
+{
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+
+    let colors = colors.collect::<Vec<_>>();
+    write_varint(&mut writer, colors.len() as u64)?;
+    for color in &colors {
+        write_varint(&mut writer, color.name().len() as u64)?;
+        writer.write_all(color.name().as_bytes())?;
+    }
+
+    write_varint(&mut writer, games.len() as u64)?;
+    for (id, analysis) in games {
+        write_varint(&mut writer, id)?;
+        write_varint(&mut writer, analysis.maxima().count() as u64)?;
+        for (color, max) in analysis.maxima() {
+            let number = colors
+                .binary_search(&color)
+                .map_err(|_| Error::InvalidInput(format!("game {id}: unindexed color {color}")))?;
+            write_varint(&mut writer, number as u64)?;
+            write_varint(&mut writer, max)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+pub(crate) fn read<R>(mut reader: R) -> Result<Snapshot>
+where
+    R: Read,
+{
+    let mut magic = [0; 8];
+    read_exact(&mut reader, &mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a game index"));
+    }
+    let mut version = [0; 2];
+    read_exact(&mut reader, &mut version)?;
+    let version = u16::from_le_bytes(version);
+    if version != VERSION {
+        return Err(invalid(&format!(
+            "unsupported version {version}, expected {VERSION}"
+        )));
+    }
+
+    let color_count = read_varint(&mut reader)?;
+    let mut colors = Vec::new();
+    for _ in 0..color_count {
+        let len = read_varint(&mut reader)?;
+        let mut name = Vec::new();
+        (&mut reader).take(len).read_to_end(&mut name)?;
+        if name.len() as u64 != len {
+            return Err(invalid("truncated"));
+        }
+        let name = String::from_utf8(name).map_err(|_| invalid("color name is not UTF-8"))?;
+        colors.push(Color::new(&name));
+    }
+
+    let game_count = read_varint(&mut reader)?;
+    let mut games = Vec::new();
+    for _ in 0..game_count {
+        let id = read_varint(&mut reader)?;
+        let entry_count = read_varint(&mut reader)?;
+        let mut maxima = Vec::new();
+        for _ in 0..entry_count {
+            let number = read_varint(&mut reader)?;
+            let color = usize::try_from(number)
+                .ok()
+                .and_then(|number| colors.get(number))
+                .ok_or_else(|| invalid(&format!("game {id}: no color number {number}")))?;
+            maxima.push((color.clone(), read_varint(&mut reader)?));
+        }
+        games.push((id, maxima.into_iter().collect()));
+    }
+
+    Ok(Snapshot { colors, games })
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidInput(format!("game index: {message}"))
+}
+
+fn read_exact<R>(reader: &mut R, buf: &mut [u8]) -> Result<()>
+where
+    R: Read,
+{
+    reader.read_exact(buf).map_err(|error| match error.kind() {
+        ErrorKind::UnexpectedEof => invalid("truncated"),
+        _ => error.into(),
+    })
+}
+
+fn write_varint<W>(writer: &mut W, mut value: u64) -> Result<()>
+where
+    W: Write,
+{
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            writer.write_all(&[byte])?;
+            return Ok(());
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R>(reader: &mut R) -> Result<u64>
+where
+    R: Read,
+{
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        read_exact(reader, &mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid("varint is too long"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0,        &[0x00])]
+    #[test_case(127,      &[0x7f])]
+    #[test_case(128,      &[0x80, 0x01])]
+    #[test_case(300,      &[0xac, 0x02])]
+    #[test_case(u64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01])]
+    fn test_varint(value: u64, expected: &[u8]) {
+        let mut bytes = Vec::new();
+        write_varint(&mut bytes, value).unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), value);
+    }
+}
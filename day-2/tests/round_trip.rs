@@ -0,0 +1,55 @@
+//! Round trips between `Game`'s canonical `Display` and `Game::parse`.
+
+use day_2::color::{Color, ColorSet};
+use day_2::game::{Game, GameIteration};
+use proptest::prelude::*;
+
+fn color() -> impl Strategy<Value = Color> {
+    prop_oneof![
+        prop::sample::select(vec!["red", "green", "blue"]),
+        prop::sample::select(vec!["yellow", "purple", "light-blue", "grün"]),
+    ]
+    .prop_map(Color::new)
+}
+
+fn iteration() -> impl Strategy<Value = GameIteration> {
+    prop::collection::vec((color(), 0..=100u64), 0..6)
+        .prop_map(|counts| counts.into_iter().collect())
+}
+
+/// A draw as a log might write it: any color order, zero counts, repeated colors.
+fn raw_draw() -> impl Strategy<Value = String> {
+    prop::collection::vec((0..=100u64, color()), 0..6).prop_map(|entries| {
+        entries
+            .iter()
+            .map(|(count, color)| format!("{count} {color}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    })
+}
+
+fn game() -> impl Strategy<Value = Game> {
+    (any::<u64>(), prop::collection::vec(iteration(), 0..6))
+        .prop_map(|(id, iterations)| Game::new(id, iterations))
+}
+
+proptest! {
+    #[test]
+    fn display_round_trips(game in game()) {
+        prop_assert!(game.iterations().iter().all(|iteration| !iteration.is_empty()));
+        let line = game.to_string();
+        let parsed = Game::parse(&line, &mut ColorSet::discovering()).unwrap();
+        prop_assert_eq!(&parsed, &game);
+        prop_assert_eq!(parsed.to_string(), line);
+    }
+
+    #[test]
+    fn display_normalizes(id in any::<u64>(), draws in prop::collection::vec(raw_draw(), 0..6)) {
+        let line = format!("Game {id}: {}", draws.join("; "));
+        let parsed = Game::parse(&line, &mut ColorSet::discovering()).unwrap();
+        let canonical = parsed.to_string();
+        let reparsed = Game::parse(&canonical, &mut ColorSet::discovering()).unwrap();
+        prop_assert_eq!(&reparsed, &parsed);
+        prop_assert_eq!(reparsed.to_string(), canonical);
+    }
+}
@@ -1,13 +1,13 @@
 use crate::gear::{GearBuilder, GearState};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
-use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 use std::rc::Rc;
 
+use aoc_common::{get_input_reader, Result};
+
 use crate::node::Node;
 use crate::part_number::{PartNumberBuilder, PartNumbers};
 
@@ -18,12 +18,17 @@ pub struct EngineSchematic {
 }
 
 impl EngineSchematic {
-    pub fn try_from_file<P>(path: P) -> Result<Self, Box<dyn Error>>
+    pub fn try_from_file<P>(path: P) -> Result<Self>
     where
         P: AsRef<Path>,
     {
-        let reader = BufReader::new(File::open(path)?);
+        Self::try_from_reader(get_input_reader(path)?)
+    }
 
+    pub fn try_from_reader<R>(reader: R) -> Result<Self>
+    where
+        R: BufRead,
+    {
         let mut nodes = Vec::new();
         let mut part_numbers = Vec::new();
 
@@ -37,7 +42,6 @@ impl EngineSchematic {
         for (y, line) in reader.lines().enumerate() {
             let mut row = Vec::new();
             for (x, character) in line?.chars().enumerate() {
-                println!("\n\n===== ROW {y}, CHARACTER {x}: '{character}' =====\n\n");
                 let node = Node::new(character);
 
                 match node {
@@ -76,10 +80,6 @@ impl EngineSchematic {
                                 }
                                 if part_number_builder.borrow().buildable() {
                                     let built = part_number_builder.borrow().build();
-                                    println!(
-                                        "Built: {}, current char: {}, code loc: Node::Digit",
-                                        built, character
-                                    );
                                     part_numbers.push(built);
                                     part_number_builder.borrow().nodes().iter().for_each(|n| {
                                         if n != &(x, y) {
@@ -125,10 +125,6 @@ impl EngineSchematic {
                                 part_number_builder.borrow_mut().validate();
                                 if part_number_builder.borrow().buildable() {
                                     let built = part_number_builder.borrow().build();
-                                    println!(
-                                        "Built: {}, current char: {}, code loc: Node::Symbol",
-                                        built, character
-                                    );
                                     part_numbers.push(built);
                                     part_number_builder.borrow().nodes().iter().for_each(|n| {
                                         unvalidated_part_numbers.remove(n);
@@ -144,10 +140,6 @@ impl EngineSchematic {
                         part_number_builder.borrow_mut().complete();
                         if part_number_builder.borrow().buildable() {
                             let built = part_number_builder.borrow().build();
-                            println!(
-                                "Built: {}, current char: {}, code loc: !matches",
-                                built, character
-                            );
                             part_numbers.push(built);
                             part_number_builder.borrow().nodes().iter().for_each(|n| {
                                 unvalidated_part_numbers.remove(n);
@@ -168,29 +160,6 @@ impl EngineSchematic {
                     unvalidated_gears.remove(&coords);
                 }
 
-                println!("AFTER:\n");
-                println!(
-                    "Current part number builder: {:?}",
-                    current_part_number_builder
-                );
-                println!(
-                    "Unvalidated part numbers: {:?}",
-                    unvalidated_part_numbers
-                        .keys()
-                        .into_iter()
-                        .collect::<Vec<_>>()
-                );
-                println!(
-                    "Unvalidated gears: {:?}",
-                    unvalidated_gears
-                        .iter()
-                        .map(|(k, v)| (*k, v.borrow().get_state()))
-                        .collect::<Vec<_>>()
-                );
-                println!("Symbols: {:?}", symbols);
-                println!("Part numbers: {:?}", part_numbers);
-                println!("-------------------");
-
                 row.push(node);
             }
 
@@ -198,7 +167,6 @@ impl EngineSchematic {
                 part_number_builder.borrow_mut().complete();
                 if part_number_builder.borrow().buildable() {
                     let built = part_number_builder.borrow().build();
-                    println!("Built: {}, code loc: row-end", built);
                     part_numbers.push(built);
                     part_number_builder.borrow().nodes().iter().for_each(|n| {
                         unvalidated_part_numbers.remove(n);
@@ -255,7 +223,7 @@ fn compute_back_neighbors(x: usize, y: usize) -> Vec<(usize, usize)> {
 mod tests {
     use super::*;
 
-    static INPUT_PATH: &str = "files/test.txt";
+    static INPUT_PATH: &str = aoc_common::input_path!("files/test.txt");
 
     #[test]
     fn test_build() {
@@ -0,0 +1,36 @@
+pub mod engine_schematic;
+mod gear;
+mod node;
+mod part_number;
+
+use std::io::BufRead;
+
+use aoc_common::{input_path, Result, Solution};
+
+use engine_schematic::EngineSchematic;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const FILES_DIR: &'static str = input_path!("files");
+
+    type Input = EngineSchematic;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse<R>(reader: R) -> Result<Self::Input>
+    where
+        R: BufRead,
+    {
+        EngineSchematic::try_from_reader(reader)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(input.part_numbers.iter().sum())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(input.gear_ratios.iter().sum())
+    }
+}
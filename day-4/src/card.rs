@@ -1,20 +1,22 @@
 use std::collections::HashSet;
-use std::error::Error;
 
-pub fn try_score_card(input: &str) -> Result<(usize, usize), Box<dyn Error>> {
-    let (label, info) = input.split_once(':').ok_or("Invalid input")?;
-    let (_, id) = label.split_once(' ').ok_or("Invalid input")?;
-    let (winners, picks) = info.trim().split_once(" | ").ok_or("Invalid input")?;
+use aoc_common::{Error, Result};
+
+pub fn try_score_card(input: &str) -> Result<(usize, usize)> {
+    let invalid_input = || Error::InvalidInput(input.to_string());
+    let (label, info) = input.split_once(':').ok_or_else(invalid_input)?;
+    let (_, id) = label.split_once(' ').ok_or_else(invalid_input)?;
+    let (winners, picks) = info.trim().split_once(" | ").ok_or_else(invalid_input)?;
     let winners = winners
         .split(' ')
         .filter(|s| !s.is_empty())
         .map(|n| n.parse::<usize>())
-        .collect::<Result<HashSet<usize>, _>>()?;
+        .collect::<std::result::Result<HashSet<usize>, _>>()?;
     let picks: Vec<usize> = picks
         .split(' ')
         .filter(|s| !s.is_empty())
         .map(|n| n.parse::<usize>())
-        .collect::<Result<Vec<usize>, _>>()?;
+        .collect::<std::result::Result<Vec<usize>, _>>()?;
 
     let score = picks.into_iter().filter(|n| winners.contains(n)).count();
 
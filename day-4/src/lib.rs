@@ -0,0 +1,52 @@
+mod card;
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::ops::Add;
+
+use aoc_common::{input_path, Result, Solution};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use crate::card::try_score_card;
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const FILES_DIR: &'static str = input_path!("files");
+
+    /// `(id, score)` for every card, where the score is the number of winning picks.
+    type Input = Vec<(usize, usize)>;
+    type PartOne = u64;
+    type PartTwo = BigUint;
+
+    fn parse<R>(reader: R) -> Result<Self::Input>
+    where
+        R: BufRead,
+    {
+        reader.lines().map(|line| try_score_card(&line?)).collect()
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(input
+            .iter()
+            .filter(|(_, card_score)| *card_score > 0)
+            .map(|(_, card_score)| 1u64 << (card_score - 1))
+            .sum())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        let mut multipliers: HashMap<usize, BigUint> = HashMap::new();
+        for &(id, card_score) in input {
+            let current_multiplier = multipliers.entry(id).or_insert(One::one()).to_owned();
+            for i in (id + 1)..=(id + card_score) {
+                let multiplier = multipliers.entry(i).or_insert(One::one()).clone();
+                multipliers.insert(i, multiplier.add(&current_multiplier).clone());
+            }
+        }
+        Ok(multipliers
+            .values()
+            .fold(Zero::zero(), |acc: BigUint, x| acc.add(x)))
+    }
+}